@@ -17,7 +17,7 @@ entity data_ram is
 	port (
 		iCLK : in std_logic;
 		iRST : in std_logic;
-		iA : in std_logic_vector(7 downto 0);
+		iA : in std_logic_vector({address_msb} downto 0);
 		iD : in std_logic_vector(15 downto 0);
 		iWE : in std_logic;
 		oQ : out std_logic_vector(15 downto 0)
@@ -26,7 +26,7 @@ end data_ram;
 
 architecture arch of data_ram is
 
-	type tMEM is array(0 to 255) of std_logic_vector(15 downto 0);
+	type tMEM is array(0 to {last_address}) of std_logic_vector(15 downto 0);
 	signal rMEM : tMEM;
 	signal sMEM : tMEM := (others => x"0000");
 
@@ -34,7 +34,7 @@ begin
 
 	process (iCLK, iRST)begin
 		if iRST = '1' then
-			for i in 0 to 255 loop
+			for i in 0 to {last_address} loop
 				rMEM(i) <= sMEM(i);
 			end loop;
 		elsif rising_edge(iCLK) then
@@ -15,8 +15,8 @@ use ieee.std_logic_unsigned.all;
 
 entity instr_rom is
 	port(
-		iA : in  std_logic_vector(7 downto 0);
-		oQ : out std_logic_vector(14 downto 0)
+		iA : in  std_logic_vector({address_msb} downto 0);
+		oQ : out std_logic_vector({instruction_msb} downto 0)
 	);
 end instr_rom;
 
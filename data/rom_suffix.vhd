@@ -1,3 +1,3 @@
 ------------------------------------------------------------------
-        "000000000000000";
+        "{nop}";
 end architecture;
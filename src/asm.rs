@@ -1,12 +1,10 @@
-use crate::{asm, instructions::Instruction};
+use lprsemu::{asm, Instruction};
 
 #[allow(dead_code)]
 pub const ROM_BIN: [&str; 0] = [];
 
-#[allow(dead_code)]
 pub const DATA_MEMORY: &[u16] = &[0, 5, 6];
 
-#[allow(dead_code)]
 pub const ROM_ASM: &[Instruction] = &asm![
     inc 0, 0; // set R0 index to 1
     ld 1, 0; // load operand from mem[1]
@@ -0,0 +1,145 @@
+use lprsemu::{load, parser, trace, MachineConfig, Processor};
+use std::error::Error;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+const CONTEXT: usize = 5;
+const MAX_STEPS: usize = 1_000_000;
+
+fn print_help() {
+    println!("{} {}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"),);
+    println!("{}", env!("CARGO_PKG_DESCRIPTION"));
+    println!("{}", env!("CARGO_PKG_AUTHORS"));
+    println!();
+    println!(
+        "Usage: {} <left.asm> <right.asm> [--data] [--context <n>] [--max-steps <n>]",
+        env!("CARGO_BIN_NAME")
+    );
+    println!("         [--machine <rom>,<ram>,<registers>]");
+    println!("  --data            Run left program with data section of right file");
+    println!("  --context <n>     Number of steps shown before divergence ({CONTEXT})");
+    println!("  --max-steps <n>   Stop comparing after n steps ({MAX_STEPS})");
+    println!("  --machine <rom>,<ram>,<registers>");
+    println!(
+        "                    Memory sizes and register count ({})",
+        MachineConfig::default()
+    );
+}
+
+fn print_usage() {
+    eprintln!(
+        "Usage: {} <left.asm> <right.asm> [--data] [--context <n>] [--max-steps <n>]",
+        env!("CARGO_BIN_NAME")
+    );
+    eprintln!("         [--machine <rom>,<ram>,<registers>]");
+}
+
+/// Parses the value following an option, naming the option if it is
+/// missing or invalid.
+fn option_value<T: FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+    match value {
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("invalid value for {option}: {value}")),
+        None => Err(format!("missing value for {option}")),
+    }
+}
+
+fn load(
+    path: &str,
+    data: Option<&str>,
+    config: &MachineConfig,
+) -> Result<Processor, Box<dyn Error>> {
+    let (rom, mut ram, labels) = parser::parse_file_with(path, config)?;
+    if let Some(data) = data {
+        ram = parser::parse_file_with(data, config)?.1;
+    }
+    let mut p = Processor::with_config(*config);
+    load::load_cpu(&mut p, Some(&rom), Some(&ram), Some(labels))?;
+    Ok(p)
+}
+
+struct Options {
+    paths: Vec<String>,
+    data: bool,
+    context: usize,
+    max_steps: usize,
+    config: MachineConfig,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        paths: Vec::new(),
+        data: false,
+        context: CONTEXT,
+        max_steps: MAX_STEPS,
+        config: MachineConfig::default(),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--data" => options.data = true,
+            "--context" => options.context = option_value(&arg, args.next())?,
+            "--max-steps" => options.max_steps = option_value(&arg, args.next())?,
+            "--machine" => options.config = option_value(&arg, args.next())?,
+            _ if arg.starts_with("--") => return Err(format!("unexpected {arg}")),
+            _ => options.paths.push(arg),
+        }
+    }
+    Ok(options)
+}
+
+fn differ(options: Options) -> Result<bool, Box<dyn Error>> {
+    let Options {
+        paths,
+        data,
+        context,
+        max_steps,
+        config,
+    } = options;
+    let [left, right] = paths.as_slice() else {
+        print_help();
+        return Err("Expected two assembly files".into());
+    };
+    let mut right_cpu = match data {
+        true => load(left, Some(right), &config)?,
+        false => load(right, None, &config)?,
+    };
+    let mut left_cpu = load(left, None, &config)?;
+    match trace::diff(&mut left_cpu, &mut right_cpu, context, max_steps)? {
+        Some(divergence) => {
+            print!("{divergence}");
+            Ok(false)
+        }
+        None if left_cpu.finished() && right_cpu.finished() => {
+            println!(
+                "No divergence in {} step(s)",
+                left_cpu.runtime_counter().max(right_cpu.runtime_counter())
+            );
+            Ok(true)
+        }
+        None => {
+            println!("No divergence before step limit of {max_steps} was reached");
+            Ok(true)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Argument error: {e}");
+            print_usage();
+            return ExitCode::from(2);
+        }
+    };
+    match differ(options) {
+        Ok(false) => ExitCode::from(1),
+        Ok(true) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(2)
+        }
+    }
+}
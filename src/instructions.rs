@@ -1,3 +1,9 @@
+//! Instructions of the LPRS1 instruction set, with their text and binary
+//! encodings and VHDL output.
+//!
+//! Register operands are indices named `z` for the destination and `x` and
+//! `y` for the sources, in the order they appear in assembly.
+
 mod display;
 mod from_str;
 mod implementation;
@@ -5,61 +11,99 @@ mod r#macro;
 mod to_vhdl;
 
 pub use implementation::RegisterBoundCheck;
-#[allow(unused_imports)]
 pub use to_vhdl::ToVhdl;
 
+/// Instructions computing a register from registers and setting the flags.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AluInstruction {
+    /// `mov z, x`: copies `x` into `z`.
     Move(u8, u8),
+    /// `add z, x, y`: stores `x + y` in `z`.
     Add(u8, u8, u8),
+    /// `sub z, x, y`: stores `x - y` in `z`.
     Subtract(u8, u8, u8),
+    /// `and z, x, y`: stores the bitwise and of `x` and `y` in `z`.
     LogicalAnd(u8, u8, u8),
+    /// `or z, x, y`: stores the bitwise or of `x` and `y` in `z`.
     LogicalOr(u8, u8, u8),
+    /// `not z, x`: stores the bitwise complement of `x` in `z`.
     LogicalNot(u8, u8),
+    /// `inc z, x`: stores `x + 1` in `z`.
     Increment(u8, u8),
+    /// `dec z, x`: stores `x - 1` in `z`.
     Decrement(u8, u8),
+    /// `shl z, x`: logical shift of `x` left by one into `z`.
     LShiftLeft(u8, u8),
+    /// `shr z, x`: logical shift of `x` right by one into `z`.
     LShiftRight(u8, u8),
+    /// `ashl z, x`: arithmetic shift of `x` left by one into `z`.
     AShiftLeft(u8, u8),
+    /// `ashr z, x`: arithmetic shift of `x` right by one into `z`.
     AShiftRight(u8, u8),
 }
 
+/// Instructions moving words between registers and data memory.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MemoryInstruction {
+    /// `ld z, y`: loads the word at the address in `y` into `z`.
     Load(u8, u8),
+    /// `st x, y`: stores `x` at the address in `y`.
     Store(u8, u8),
 }
 
+/// Jumps to a program memory address, either always or depending on a
+/// flag.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ControlFlowInstruction {
+    /// `jmp`: always jumps.
     Jump(u16),
+    /// `jmpz`: jumps if the zero flag is set.
     JumpZero(u16),
+    /// `jmps`: jumps if the sign flag is set.
     JumpSign(u16),
+    /// `jmpc`: jumps if the carry flag is set.
     JumpCarry(u16),
+    /// `jmpnz`: jumps if the zero flag is clear.
     JumpNotZero(u16),
+    /// `jmpns`: jumps if the sign flag is clear.
     JumpNotSign(u16),
+    /// `jmpnc`: jumps if the carry flag is clear.
     JumpNotCarry(u16),
 }
 
-#[allow(unused)]
+/// Instructions which only exist in the emulator, for setting up state
+/// and stopping programs.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum DebugInstruction {
+    /// `sreg`: sets a register to a value.
     SetRegister(u8, u16),
+    /// `sfz`: sets the zero flag.
     SetFlagZero(bool),
+    /// `sfs`: sets the sign flag.
     SetFlagSign(bool),
+    /// `sfc`: sets the carry flag.
     SetFlagCarry(bool),
+    /// `smem`: sets a data memory address to a value.
     SetMemory(u16, u16),
+    /// `brk`: places a breakpoint on a program memory address.
     Breakpoint(u16),
+    /// `halt`: stops the program by moving the program counter past the program
+    /// memory.
     Halt,
 }
 
+/// A single instruction of program memory.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum Instruction {
+    /// Arithmetic or logic instruction.
     Alu(AluInstruction),
+    /// Load or store.
     Memory(MemoryInstruction),
+    /// Jump.
     ControlFlow(ControlFlowInstruction),
-    #[allow(unused)]
+    /// Emulator-only instruction.
     Debug(DebugInstruction),
+    /// `nop`, which also fills the program memory after the program.
     #[default]
     NoOperation,
 }
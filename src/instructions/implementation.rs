@@ -1,69 +1,93 @@
 use super::{AluInstruction, ControlFlowInstruction, Instruction, MemoryInstruction};
+use crate::processor::MachineConfig;
 
 macro_rules! in_range {
-    ($thresh:ident; $($v:expr),*) => {
-        $(($v as usize) < ($crate::processor::$thresh as usize))&&*
+    ($thresh:expr; $($v:expr),*) => {
+        $(($v as usize) < $thresh)&&*
     };
 }
 
+/// Checks instruction operands against the size of a machine.
 pub trait RegisterBoundCheck {
-    fn reg_bound_check(&self) -> bool;
+    /// Whether every register and jump target exists in the machine.
+    fn reg_bound_check(&self, config: &MachineConfig) -> bool;
 }
 
 impl RegisterBoundCheck for Instruction {
-    fn reg_bound_check(&self) -> bool {
+    fn reg_bound_check(&self, config: &MachineConfig) -> bool {
         match *self {
-            Self::Alu(i) => i.reg_bound_check(),
-            Self::Memory(i) => i.reg_bound_check(),
-            Self::ControlFlow(i) => i.reg_bound_check(),
+            Self::Alu(i) => i.reg_bound_check(config),
+            Self::Memory(i) => i.reg_bound_check(config),
+            Self::ControlFlow(i) => i.reg_bound_check(config),
             _ => true,
         }
     }
 }
 
 impl RegisterBoundCheck for AluInstruction {
-    fn reg_bound_check(&self) -> bool {
+    fn reg_bound_check(&self, config: &MachineConfig) -> bool {
         match *self {
-            Self::Move(a, b) => in_range!(REG_COUNT; a, b),
-            Self::Add(a, b, c) => in_range!(REG_COUNT; a, b, c),
-            Self::Subtract(a, b, c) => in_range!(REG_COUNT; a, b, c),
-            Self::LogicalAnd(a, b, c) => in_range!(REG_COUNT; a, b, c),
-            Self::LogicalOr(a, b, c) => in_range!(REG_COUNT; a, b, c),
-            Self::LogicalNot(a, b) => in_range!(REG_COUNT; a, b),
-            Self::Increment(a, b) => in_range!(REG_COUNT; a, b),
-            Self::Decrement(a, b) => in_range!(REG_COUNT; a, b),
-            Self::LShiftLeft(a, b) => in_range!(REG_COUNT; a, b),
-            Self::LShiftRight(a, b) => in_range!(REG_COUNT; a, b),
-            Self::AShiftLeft(a, b) => in_range!(REG_COUNT; a, b),
-            Self::AShiftRight(a, b) => in_range!(REG_COUNT; a, b),
+            Self::Move(a, b) => in_range!(config.register_count(); a, b),
+            Self::Add(a, b, c) => in_range!(config.register_count(); a, b, c),
+            Self::Subtract(a, b, c) => in_range!(config.register_count(); a, b, c),
+            Self::LogicalAnd(a, b, c) => in_range!(config.register_count(); a, b, c),
+            Self::LogicalOr(a, b, c) => in_range!(config.register_count(); a, b, c),
+            Self::LogicalNot(a, b) => in_range!(config.register_count(); a, b),
+            Self::Increment(a, b) => in_range!(config.register_count(); a, b),
+            Self::Decrement(a, b) => in_range!(config.register_count(); a, b),
+            Self::LShiftLeft(a, b) => in_range!(config.register_count(); a, b),
+            Self::LShiftRight(a, b) => in_range!(config.register_count(); a, b),
+            Self::AShiftLeft(a, b) => in_range!(config.register_count(); a, b),
+            Self::AShiftRight(a, b) => in_range!(config.register_count(); a, b),
         }
     }
 }
 
 impl RegisterBoundCheck for MemoryInstruction {
-    fn reg_bound_check(&self) -> bool {
+    fn reg_bound_check(&self, config: &MachineConfig) -> bool {
         match *self {
-            Self::Load(a, b) => in_range!(REG_COUNT; a, b),
-            Self::Store(a, b) => in_range!(REG_COUNT; a, b),
+            Self::Load(a, b) => in_range!(config.register_count(); a, b),
+            Self::Store(a, b) => in_range!(config.register_count(); a, b),
         }
     }
 }
 
 impl RegisterBoundCheck for ControlFlowInstruction {
-    fn reg_bound_check(&self) -> bool {
+    fn reg_bound_check(&self, config: &MachineConfig) -> bool {
         match *self {
-            Self::Jump(a) => in_range!(ROM_SIZE; a),
-            Self::JumpZero(a) => in_range!(ROM_SIZE; a),
-            Self::JumpSign(a) => in_range!(ROM_SIZE; a),
-            Self::JumpCarry(a) => in_range!(ROM_SIZE; a),
-            Self::JumpNotZero(a) => in_range!(ROM_SIZE; a),
-            Self::JumpNotSign(a) => in_range!(ROM_SIZE; a),
-            Self::JumpNotCarry(a) => in_range!(ROM_SIZE; a),
+            Self::Jump(a) => in_range!(config.rom_size(); a),
+            Self::JumpZero(a) => in_range!(config.rom_size(); a),
+            Self::JumpSign(a) => in_range!(config.rom_size(); a),
+            Self::JumpCarry(a) => in_range!(config.rom_size(); a),
+            Self::JumpNotZero(a) => in_range!(config.rom_size(); a),
+            Self::JumpNotSign(a) => in_range!(config.rom_size(); a),
+            Self::JumpNotCarry(a) => in_range!(config.rom_size(); a),
+        }
+    }
+}
+
+impl AluInstruction {
+    /// Register the instruction writes.
+    pub fn get_destination(&self) -> u8 {
+        match *self {
+            Self::Move(z, _) => z,
+            Self::Add(z, _, _) => z,
+            Self::Subtract(z, _, _) => z,
+            Self::LogicalAnd(z, _, _) => z,
+            Self::LogicalOr(z, _, _) => z,
+            Self::LogicalNot(z, _) => z,
+            Self::Increment(z, _) => z,
+            Self::Decrement(z, _) => z,
+            Self::LShiftLeft(z, _) => z,
+            Self::LShiftRight(z, _) => z,
+            Self::AShiftLeft(z, _) => z,
+            Self::AShiftRight(z, _) => z,
         }
     }
 }
 
 impl ControlFlowInstruction {
+    /// Program memory address the instruction jumps to.
     pub fn get_address(&self) -> u16 {
         match *self {
             ControlFlowInstruction::Jump(a) => a,
@@ -75,4 +99,9 @@ impl ControlFlowInstruction {
             ControlFlowInstruction::JumpNotCarry(a) => a,
         }
     }
+
+    /// Whether the jump depends on a flag.
+    pub fn is_conditional(&self) -> bool {
+        !matches!(self, ControlFlowInstruction::Jump(_))
+    }
 }
@@ -1,3 +1,5 @@
+/// Builds a single [`Instruction`](crate::instructions::Instruction) from
+/// its assembly mnemonic, e.g. `op![add 0, 1, 2]` or `op![jmpz 4]`.
 #[macro_export]
 macro_rules! op {
     (alu $n:ident $($r:tt),*) => {
@@ -101,6 +103,7 @@ macro_rules! op {
     };
 }
 
+/// Builds an array of instructions from `;`-separated [`op!`] mnemonics.
 #[macro_export]
 macro_rules! asm {
     ($($instr:tt $($operand:tt),*);*) => {
@@ -1,68 +1,92 @@
 use crate::instructions::{AluInstruction, ControlFlowInstruction, Instruction, MemoryInstruction};
+use crate::processor::MachineConfig;
 
 const EMPTY_REG: u8 = 0;
 
+/// Binary encoding of an instruction for a machine configuration.
 pub trait ToVhdl {
-    fn to_vhdl(&self) -> String;
+    /// Instruction word as a string of bits, empty for instructions the CPU
+    /// does not have.
+    fn to_vhdl(&self, config: &MachineConfig) -> String;
 }
 
 impl ToVhdl for Instruction {
-    fn to_vhdl(&self) -> String {
+    fn to_vhdl(&self, config: &MachineConfig) -> String {
         match *self {
-            Self::Alu(ins) => ins.to_vhdl(),
-            Self::Memory(ins) => ins.to_vhdl(),
-            Self::ControlFlow(ins) => ins.to_vhdl(),
+            Self::Alu(ins) => ins.to_vhdl(config),
+            Self::Memory(ins) => ins.to_vhdl(config),
+            Self::ControlFlow(ins) => ins.to_vhdl(config),
             _ => "".into(),
         }
     }
 }
 
 impl ToVhdl for AluInstruction {
-    fn to_vhdl(&self) -> String {
+    fn to_vhdl(&self, config: &MachineConfig) -> String {
         match *self {
-            Self::Move(rz, rx) => format!("000000{}", encode_registers(rz, rx, EMPTY_REG)),
-            Self::Add(rz, rx, ry) => format!("000001{}", encode_registers(rz, rx, ry)),
-            Self::Subtract(rz, rx, ry) => format!("000010{}", encode_registers(rz, rx, ry)),
-            Self::LogicalAnd(rz, rx, ry) => format!("000011{}", encode_registers(rz, rx, ry)),
-            Self::LogicalOr(rz, rx, ry) => format!("000100{}", encode_registers(rz, rx, ry)),
-            Self::LogicalNot(rz, rx) => format!("000101{}", encode_registers(rz, rx, EMPTY_REG)),
-            Self::Increment(rz, rx) => format!("000110{}", encode_registers(rz, rx, EMPTY_REG)),
-            Self::Decrement(rz, rx) => format!("000111{}", encode_registers(rz, rx, EMPTY_REG)),
-            Self::LShiftLeft(rz, rx) => format!("001000{}", encode_registers(rz, rx, EMPTY_REG)),
-            Self::LShiftRight(rz, rx) => format!("001001{}", encode_registers(rz, rx, EMPTY_REG)),
-            Self::AShiftLeft(rz, rx) => format!("001010{}", encode_registers(rz, rx, EMPTY_REG)),
-            Self::AShiftRight(rz, rx) => format!("001011{}", encode_registers(rz, rx, EMPTY_REG)),
+            Self::Move(rz, rx) => format!("000000{}", encode_registers(config, rz, rx, EMPTY_REG)),
+            Self::Add(rz, rx, ry) => format!("000001{}", encode_registers(config, rz, rx, ry)),
+            Self::Subtract(rz, rx, ry) => format!("000010{}", encode_registers(config, rz, rx, ry)),
+            Self::LogicalAnd(rz, rx, ry) => {
+                format!("000011{}", encode_registers(config, rz, rx, ry))
+            }
+            Self::LogicalOr(rz, rx, ry) => {
+                format!("000100{}", encode_registers(config, rz, rx, ry))
+            }
+            Self::LogicalNot(rz, rx) => {
+                format!("000101{}", encode_registers(config, rz, rx, EMPTY_REG))
+            }
+            Self::Increment(rz, rx) => {
+                format!("000110{}", encode_registers(config, rz, rx, EMPTY_REG))
+            }
+            Self::Decrement(rz, rx) => {
+                format!("000111{}", encode_registers(config, rz, rx, EMPTY_REG))
+            }
+            Self::LShiftLeft(rz, rx) => {
+                format!("001000{}", encode_registers(config, rz, rx, EMPTY_REG))
+            }
+            Self::LShiftRight(rz, rx) => {
+                format!("001001{}", encode_registers(config, rz, rx, EMPTY_REG))
+            }
+            Self::AShiftLeft(rz, rx) => {
+                format!("001010{}", encode_registers(config, rz, rx, EMPTY_REG))
+            }
+            Self::AShiftRight(rz, rx) => {
+                format!("001011{}", encode_registers(config, rz, rx, EMPTY_REG))
+            }
         }
     }
 }
 
 impl ToVhdl for MemoryInstruction {
-    fn to_vhdl(&self) -> String {
+    fn to_vhdl(&self, config: &MachineConfig) -> String {
         match *self {
-            Self::Load(rz, ry) => format!("100000{}", encode_registers(rz, EMPTY_REG, ry)),
-            Self::Store(rx, ry) => format!("110000{}", encode_registers(EMPTY_REG, rx, ry)),
+            Self::Load(rz, ry) => format!("100000{}", encode_registers(config, rz, EMPTY_REG, ry)),
+            Self::Store(rx, ry) => format!("110000{}", encode_registers(config, EMPTY_REG, rx, ry)),
         }
     }
 }
 
 impl ToVhdl for ControlFlowInstruction {
-    fn to_vhdl(&self) -> String {
+    fn to_vhdl(&self, config: &MachineConfig) -> String {
         match *self {
-            Self::Jump(a) => format!("010000{}", encode_address(a)),
-            Self::JumpZero(a) => format!("010001{}", encode_address(a)),
-            Self::JumpSign(a) => format!("010010{}", encode_address(a)),
-            Self::JumpCarry(a) => format!("010011{}", encode_address(a)),
-            Self::JumpNotZero(a) => format!("010101{}", encode_address(a)),
-            Self::JumpNotSign(a) => format!("010110{}", encode_address(a)),
-            Self::JumpNotCarry(a) => format!("010111{}", encode_address(a)),
+            Self::Jump(a) => format!("010000{}", encode_address(config, a)),
+            Self::JumpZero(a) => format!("010001{}", encode_address(config, a)),
+            Self::JumpSign(a) => format!("010010{}", encode_address(config, a)),
+            Self::JumpCarry(a) => format!("010011{}", encode_address(config, a)),
+            Self::JumpNotZero(a) => format!("010101{}", encode_address(config, a)),
+            Self::JumpNotSign(a) => format!("010110{}", encode_address(config, a)),
+            Self::JumpNotCarry(a) => format!("010111{}", encode_address(config, a)),
         }
     }
 }
 
-fn encode_registers(r1: u8, r2: u8, r3: u8) -> String {
-    format!("{r1:03b}{r2:03b}{r3:03b}")
+fn encode_registers(config: &MachineConfig, r1: u8, r2: u8, r3: u8) -> String {
+    let width = config.register_bits();
+    let registers = format!("{r1:0width$b}{r2:0width$b}{r3:0width$b}");
+    format!("{registers:0>width$}", width = config.operand_bits())
 }
 
-fn encode_address(a: u16) -> String {
-    format!("{a:09b}")
+fn encode_address(config: &MachineConfig, a: u16) -> String {
+    format!("{a:0width$b}", width = config.operand_bits())
 }
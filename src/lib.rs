@@ -0,0 +1,33 @@
+//! Emulator, debugger and assembler for LPRS1 ISA & CPU.
+//!
+//! The crate is split into an assembly [`parser`], the [`instructions`] of
+//! the instruction set and a [`processor`] which executes them. Both the
+//! `lprsemu` debugger and the `lprsasm` assembler are built on top of it.
+
+#![warn(missing_docs)]
+
+#[macro_use]
+extern crate pest_derive;
+
+pub mod instructions;
+pub mod load;
+pub mod parser;
+pub mod peripheral;
+pub mod processor;
+pub mod trace;
+pub mod vhdl;
+
+pub use instructions::{
+    AluInstruction, ControlFlowInstruction, DebugInstruction, Instruction, MemoryInstruction,
+    RegisterBoundCheck, ToVhdl,
+};
+pub use load::LoadError;
+pub use parser::{
+    parse_expression, parse_file, parse_file_with, parse_location, parse_log_format, AsmFileData,
+    Expression, Location, LogFormat, ParsingError,
+};
+pub use processor::{
+    Breakpoint, Coverage, CycleModel, DisplayRadix, DisplaySigned, EmulationError, Flag,
+    FlagRegisters, MachineConfig, Processor, Profile, RegisterWatch, RegisterWatchKind, RunOutcome,
+    StopReason, WatchKind, Watchpoint,
+};
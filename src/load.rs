@@ -1,18 +1,67 @@
+//! Loading programs into a processor.
+
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::time::SystemTime;
 
 use crate::instructions::Instruction;
-use crate::parser::parse_file;
-use crate::processor::Processor;
+use crate::parser::{parse_file_with, ParsingError};
+use crate::processor::{Breakpoint, MachineConfig, Processor};
+
+/// Reasons a program cannot be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The assembly file cannot be read or parsed.
+    Parsing(ParsingError),
+    /// The program or its data does not fit into the memories of the machine.
+    TooLarge(MachineConfig),
+    /// An instruction refers to a register or address the machine lacks.
+    InvalidInstruction(Instruction),
+}
+
+impl From<ParsingError> for LoadError {
+    fn from(e: ParsingError) -> Self {
+        Self::Parsing(e)
+    }
+}
 
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parsing(e) => write!(f, "{e}"),
+            Self::TooLarge(config) => {
+                write!(
+                    f,
+                    "Loading error: Program does not fit into memory ({config})"
+                )
+            }
+            Self::InvalidInstruction(ins) => {
+                write!(f, "Loading error: Instruction '{ins}' is not valid")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Loads the given memories and labels into a processor, resetting it if
+/// the program changes. Breakpoints are moved to their locations in the new
+/// program, returning the ones which no longer map to program memory.
 pub fn load_cpu(
     proc: &mut Processor,
     rom: Option<&[Instruction]>,
     ram: Option<&[u16]>,
     labels: Option<HashMap<usize, Vec<String>>>,
-) -> bool {
+) -> Result<Vec<Breakpoint>, LoadError> {
+    let config = proc.config();
+    if rom.is_some_and(|rom| rom.len() > config.rom_size())
+        || ram.is_some_and(|ram| ram.len() > config.ram_size())
+    {
+        return Err(LoadError::TooLarge(config));
+    }
+    let breakpoints = rom.map(|_| proc.take_breakpoints());
     if let Some(rom) = rom {
         proc.load_rom(rom);
-        proc.clear_breakpoints();
         proc.reset();
     }
     if let Some(ram) = ram {
@@ -21,21 +70,54 @@ pub fn load_cpu(
     if let Some(labels) = labels {
         proc.load_labels(labels);
     }
-    match proc.check() {
-        Ok(_) => true,
-        Err(ins) => {
-            eprintln!("Loading error: Instruction '{ins}' is not valid");
-            false
+    let unresolved = match breakpoints {
+        Some(breakpoints) => proc.restore_breakpoints(breakpoints),
+        None => Vec::new(),
+    };
+    proc.check().map_err(LoadError::InvalidInstruction)?;
+    Ok(unresolved)
+}
+
+/// Polls the modification time of a file.
+pub struct FileWatcher {
+    path: String,
+    modified: Option<SystemTime>,
+}
+
+impl FileWatcher {
+    /// Starts watching a file from its current modification time.
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.into(),
+            modified: modified(path),
         }
     }
-}
 
-pub fn load_from_file(proc: &mut Processor, path: &str) -> bool {
-    match parse_file(path) {
-        Ok((rom, ram, labels)) => load_cpu(proc, Some(&rom), Some(&ram), Some(labels)),
-        Err(e) => {
-            eprintln!("{e}");
-            false
+    /// Path of the watched file.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns `true` once after every change of the file.
+    pub fn changed(&mut self) -> bool {
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return false;
         }
+        self.modified = modified;
+        modified.is_some()
     }
 }
+
+fn modified(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Parses an assembly file for the machine of the processor and loads it
+/// like [`load_cpu`].
+pub fn load_from_file(proc: &mut Processor, path: &str) -> Result<Vec<Breakpoint>, LoadError> {
+    let (rom, ram, labels) = parse_file_with(path, &proc.config())?;
+    load_cpu(proc, Some(&rom), Some(&ram), Some(labels))
+}
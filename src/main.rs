@@ -1,26 +1,156 @@
-#![feature(unchecked_math)]
-#![feature(unchecked_shifts)]
+use lprsemu::load::FileWatcher;
+use lprsemu::peripheral::{self, Switches, Uart};
+use lprsemu::trace::TraceFormat;
+use lprsemu::{
+    load, parse_expression, parse_location, parse_log_format, Breakpoint, Flag, LoadError,
+    Location, MachineConfig, Processor, RegisterWatchKind, RunOutcome, StopReason, WatchKind,
+};
+use std::fs::File;
+use std::io::BufWriter;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
 
-use processor::Processor;
+mod asm;
 
-#[macro_use]
-extern crate pest_derive;
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+/// Exit code for invalid arguments or a program which cannot be loaded,
+/// distinct from those of [`RunOutcome::exit_code`].
+const EXIT_USAGE: u8 = 64;
 
-mod asm;
-mod instructions;
-mod load;
-mod parser;
-mod processor;
+struct Watch {
+    watcher: FileWatcher,
+    rerun: bool,
+}
+
+fn read_input() -> Receiver<String> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || loop {
+        let mut line = String::new();
+        match std::io::stdin().read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+            Err(_) => eprintln!("Input error"),
+        }
+    });
+    rx
+}
 
-fn prompt(separator: &str) -> Option<Vec<String>> {
+fn prompt(
+    separator: &str,
+    input: &Receiver<String>,
+    p: &mut Processor,
+    watch: &mut Option<Watch>,
+    interrupt: &AtomicBool,
+) -> Option<Vec<String>> {
     use std::io::Write;
-    let mut line = String::new();
+    interrupt.store(false, Ordering::Relaxed);
+    if let Some(e) = p.take_trace_error() {
+        eprintln!("Trace stopped: {e}");
+    }
     print!("{} {} ", env!("CARGO_PKG_NAME"), separator);
     std::io::stdout().flush().unwrap();
-    match std::io::stdin().read_line(&mut line) {
-        Ok(_) => Some(line.trim().split(' ').map(str::to_string).collect()),
-        Err(_) => None,
+    loop {
+        match input.recv_timeout(WATCH_INTERVAL) {
+            Ok(line) => return Some(line.trim().split(' ').map(str::to_string).collect()),
+            Err(RecvTimeoutError::Timeout) => {
+                if interrupt.swap(false, Ordering::Relaxed) {
+                    println!();
+                    print!("{} {} ", env!("CARGO_PKG_NAME"), separator);
+                    std::io::stdout().flush().unwrap();
+                }
+                if let Some(watch) = watch {
+                    if watch.watcher.changed() {
+                        _ = clearscreen::clear();
+                        reload(p, watch);
+                        print!("{} {} ", env!("CARGO_PKG_NAME"), separator);
+                        std::io::stdout().flush().unwrap();
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+}
+
+/// Runs without stopping on breakpoints. Unless `history` is set the run
+/// records no undo history, which also discards the history before it.
+fn run_all(p: &mut Processor, count: Option<usize>, history: bool) -> RunOutcome {
+    if history {
+        return p.run(false, count);
     }
+    let limit = p.history_limit();
+    p.set_history_limit(0);
+    let outcome = p.run(false, count);
+    p.set_history_limit(limit);
+    outcome
+}
+
+fn parse_max_steps(args: &[String]) -> Option<Option<usize>> {
+    match args {
+        [] => Some(None),
+        [flag, steps] if flag == "--max-steps" => steps.parse().ok().map(Some),
+        _ => None,
+    }
+}
+
+fn parse_frequency(text: &str) -> Option<f64> {
+    let text = text.trim_end_matches("Hz").trim_end_matches("hz");
+    let (number, multiplier) = match text.char_indices().last()? {
+        (i, 'k' | 'K') => (&text[..i], 1e3),
+        (i, 'M') => (&text[..i], 1e6),
+        (i, 'G') => (&text[..i], 1e9),
+        _ => (text, 1.0),
+    };
+    match number.parse::<f64>() {
+        Ok(number) if number > 0.0 => Some(number * multiplier),
+        _ => None,
+    }
+}
+
+fn parse_register(text: &str) -> Option<u8> {
+    text.strip_prefix(['R', 'r'])?.parse().ok()
+}
+
+/// Prints why a program could not be loaded or which breakpoints it
+/// dropped, returning whether it was loaded.
+fn report_load(result: Result<Vec<Breakpoint>, LoadError>) -> bool {
+    match result {
+        Ok(unresolved) => {
+            for breakpoint in unresolved {
+                eprintln!(
+                    "Breakpoint at '{}' no longer maps to program memory",
+                    breakpoint.location
+                );
+            }
+            true
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            false
+        }
+    }
+}
+
+fn reload(p: &mut Processor, watch: &Watch) {
+    let steps = p.runtime_counter();
+    let loaded = load::load_from_file(p, watch.watcher.path());
+    if !report_load(loaded) {
+        return;
+    }
+    if watch.rerun {
+        if let RunOutcome::Error(e) = p.run(false, Some(steps)) {
+            eprintln!("Emulation error: {e:?}");
+        }
+    }
+    println!("{p}");
+    println!("Reloaded '{}'", watch.watcher.path());
 }
 
 fn print_help() {
@@ -32,138 +162,789 @@ fn print_help() {
     println!("  p  | print             Print current state");
     println!("  l  | load-file <path>  Load program from assembly file");
     println!("  d  | radix <u/s/x/b>   Toggle decimal display form");
-    println!("  r  | run               Run until next breakpoint");
-    println!("  ra | run-all           Run to the end");
+    println!("  r  | run [--max-steps <n>]");
+    println!("                         Run until next breakpoint");
+    println!("  ra | run-all [--max-steps <n>]");
+    println!("                         Run to the end, Ctrl-C interrupts any run");
+    println!("  sl | step-limit [n/off]");
+    println!("                         Print or set maximum instructions per run");
+    println!("  lo | loop-detection [on/off]");
+    println!("                         Print or toggle stopping runs on infinite loops");
     println!("  s  | step              Execute one instruction");
-    println!("  u  | undo              Undo last instruction");
-    println!("  b  | breakpoint <line> Toggle breakpoint on line");
+    println!("  u  | undo [count]      Undo last instruction(s)");
+    println!("  hl | history-limit <n> Limit number of undoable instructions");
+    println!("  rh | run-history [on/off]");
+    println!("                         Print or toggle undo history for run-all,");
+    println!("                         which runs faster without it");
+    println!("  rc | reverse-continue  Undo until previous breakpoint");
+    println!("  rl | reverse-to-label [label]");
+    println!("                         Undo until previous (given) label");
+    println!("  b  | breakpoint <line> [if <condition>]");
+    println!("                         Line is an address, label or label+offset");
+    println!("                         Toggle breakpoint or set conditional one on line");
+    println!("  lp | logpoint <line> \"<message>\" [if <condition>]");
+    println!("                         Print message with {{expressions}} whenever line");
+    println!("                         is executed, without stopping");
+    println!("  bi | breakpoint-ignore <line> <count>");
+    println!("                         Ignore next hits of breakpoint on line");
+    println!("  bl | breakpoint-list   List all breakpoints");
     println!("  bc | breakpoint-clear  Remove all breakpoints");
+    println!("  wp | watchpoint <addr> <r/w/c>");
+    println!("                         Watch memory for reads, writes or changes");
+    println!("  wl | watchpoint-list   List all watchpoints");
+    println!("  wd | watchpoint-delete <addr> [r/w/c]");
+    println!("                         Remove watchpoints on address");
+    println!("  rw | register-watch <reg> <w/c/value>");
+    println!("                         Watch register for writes, changes or");
+    println!("                         crossing a signed threshold value");
+    println!("  rd | register-watch-delete <reg> [w/c/value]");
+    println!("                         Remove watches on register");
+    println!("  fw | flag-watch <zero/sign/carry>");
+    println!("                         Toggle watch on flag flips");
+    println!("  wc | watchpoint-clear  Remove all watchpoints and watches");
+    println!("  pa | peripheral-add <kind> <addr> [args]");
+    println!("                         Map device to data memory: leds [8/16],");
+    println!("                         switches [8/16], 7seg [digits],");
+    println!("                         matrix [8/16], uart,");
+    println!("                         timer [instructions/cycles]");
+    println!("  pl | peripheral-list   List all peripherals");
+    println!("  pd | peripheral-delete <addr>");
+    println!("                         Remove peripheral mapped to address");
+    println!("  sw | switch <addr> <index>");
+    println!("                         Flip switch of switch bank at address");
+    println!("  ui | uart-input <text> Queue line of console input");
+    println!("  uf | uart-file <path>  Queue file contents as console input");
     println!("  j  | jump <line>       Set program counter to line");
     println!("  x  | reset             Reset processor");
-    println!("  e  | benchmark         Emulation speed benchmark");
+    println!("  e  | benchmark [freq]  Emulation speed benchmark, optionally with");
+    println!("                         simulated time at clock frequency (e.g. 12MHz)");
+    println!("  cy | cycles [<class> <n>]");
+    println!("                         Print or set clock cycles of alu, memory,");
+    println!("                         jump, debug or nop instructions");
+    println!("  w  | watch [rerun/off] Reload file on change, optionally rerunning");
+    println!("                         to the same runtime counter");
+    println!("  pf | profile           Print executed instructions by label");
+    println!("  cv | coverage [path]   Print executed instructions and branches taken,");
+    println!("                         or write summary to JSON file");
+    println!("  t  | trace <path/off> [json/csv]");
+    println!("                         Record executed instructions to file");
     println!("  h  | help              Print help");
 }
 
-fn main() {
-    let mut p = Processor::default();
+fn resolve_line(p: &Processor, text: &str) -> Option<usize> {
+    match parse_location(text).and_then(|location| p.resolve(&location)) {
+        Ok(line) => Some(line),
+        Err(e) => {
+            eprintln!("{e}");
+            None
+        }
+    }
+}
+
+fn print_stop_reason(p: &Processor) {
+    match p.stop_reason() {
+        Some(reason @ StopReason::Loop { start, .. }) => match p.symbolize(start) {
+            location @ Location::Label(..) => println!("{reason} ({location})"),
+            Location::Address(_) => println!("{reason}"),
+        },
+        Some(reason) => println!("{reason}"),
+        None => {}
+    }
+}
+
+fn print_outcome(p: &Processor, outcome: RunOutcome) {
+    match outcome {
+        RunOutcome::Stopped(_) => print_stop_reason(p),
+        RunOutcome::Error(_) => eprintln!("{outcome}"),
+        _ => println!("{outcome}"),
+    }
+}
+
+fn print_usage() {
+    eprintln!(
+        "Usage: {} [file.asm] [--watch] [--batch] [--max-steps <n>]",
+        env!("CARGO_BIN_NAME")
+    );
+    eprintln!("         [--machine <rom>,<ram>,<registers>]");
+}
+
+fn main() -> ExitCode {
+    let mut config = MachineConfig::default();
+    let mut path = None;
+    let mut watch_file = false;
+    let mut batch = false;
+    let mut step_limit = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--watch" => watch_file = true,
+            "--batch" => batch = true,
+            "--machine" => match args.next().and_then(|machine| machine.parse().ok()) {
+                Some(machine) => config = machine,
+                None => {
+                    eprintln!("Argument error: expected --machine <rom>,<ram>,<registers>");
+                    return ExitCode::from(EXIT_USAGE);
+                }
+            },
+            "--max-steps" => match args.next().and_then(|steps| steps.parse().ok()) {
+                Some(steps) => step_limit = Some(steps),
+                None => {
+                    eprintln!("Argument error: expected --max-steps <n>");
+                    return ExitCode::from(EXIT_USAGE);
+                }
+            },
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => {
+                eprintln!("Argument error: unexpected {arg}");
+                print_usage();
+                return ExitCode::from(EXIT_USAGE);
+            }
+        }
+    }
+    let mut p = Processor::with_config(config);
+    let mut watch = None;
+    let mut run_history = true;
+    let interrupt = Arc::new(AtomicBool::new(false));
+    let handler = interrupt.clone();
+    if let Err(e) = ctrlc::set_handler(move || handler.store(true, Ordering::Relaxed)) {
+        eprintln!("Unable to handle Ctrl-C: {e}");
+    }
+    p.set_interrupt(interrupt.clone());
+    p.set_step_limit(step_limit);
+    p.set_log_output(Some(Box::new(std::io::stdout())));
 
-    match std::env::args().nth(1) {
+    match &path {
         Some(path) => {
-            if !load::load_from_file(&mut p, &path) {
-                return;
+            let loaded = load::load_from_file(&mut p, path);
+            if !report_load(loaded) {
+                return ExitCode::from(EXIT_USAGE);
+            }
+            if watch_file {
+                watch = Some(Watch {
+                    watcher: FileWatcher::new(path),
+                    rerun: false,
+                });
             }
         }
         None => {
-            if !load::load_cpu(&mut p, Some(asm::ROM_ASM), Some(asm::DATA_MEMORY), None) {
-                return;
+            let loaded = load::load_cpu(&mut p, Some(asm::ROM_ASM), Some(asm::DATA_MEMORY), None);
+            if !report_load(loaded) {
+                return ExitCode::from(EXIT_USAGE);
             }
         }
     };
 
+    if batch {
+        p.set_history_limit(0);
+        let outcome = p.run(true, None);
+        println!("{p}");
+        print_outcome(&p, outcome);
+        return ExitCode::from(outcome.exit_code());
+    }
+
     println!("{p}");
-    loop {
-        if let Some(input) = prompt(">>") {
-            if input.is_empty() {
-                continue;
-            }
-            _ = clearscreen::clear();
-            match input[0].as_str() {
-                "p" | "print" => println!("{p}"),
-                "l" | "load" => {
-                    if input.len() != 2 {
+    let stdin = read_input();
+    while let Some(input) = prompt(">>", &stdin, &mut p, &mut watch, &interrupt) {
+        if input.is_empty() {
+            continue;
+        }
+        _ = clearscreen::clear();
+        match input[0].as_str() {
+            "p" | "print" => println!("{p}"),
+            "l" | "load" => {
+                if input.len() != 2 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                let loaded = load::load_from_file(&mut p, input[1].as_str());
+                if report_load(loaded) {
+                    path = Some(input[1].clone());
+                    if let Some(watch) = &mut watch {
+                        watch.watcher = FileWatcher::new(&input[1]);
+                    }
+                    println!("{p}");
+                }
+            }
+            "d" | "radix" => {
+                if input.len() != 2 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                use lprsemu::{DisplayRadix, DisplaySigned};
+                match input[1].as_str() {
+                    "u" => p.set_radix(DisplayRadix::Decimal(DisplaySigned::Unsigned)),
+                    "s" => p.set_radix(DisplayRadix::Decimal(DisplaySigned::Signed)),
+                    "x" => p.set_radix(DisplayRadix::Hexadecimal),
+                    "b" => p.set_radix(DisplayRadix::Binary),
+                    _ => println!("Argument error"),
+                }
+                println!("{p}");
+            }
+            "r" | "run" => {
+                let Some(max_steps) = parse_max_steps(&input[1..]) else {
+                    eprintln!("Argument error");
+                    continue;
+                };
+                let outcome = p.run(true, max_steps);
+                if !matches!(outcome, RunOutcome::Error(_)) {
+                    println!("{p}");
+                }
+                print_outcome(&p, outcome);
+            }
+            "ra" | "run-all" => {
+                let Some(max_steps) = parse_max_steps(&input[1..]) else {
+                    eprintln!("Argument error");
+                    continue;
+                };
+                let outcome = run_all(&mut p, max_steps, run_history);
+                if !matches!(outcome, RunOutcome::Error(_)) {
+                    println!("{p}");
+                }
+                print_outcome(&p, outcome);
+            }
+            "s" | "step" | "" => {
+                if let Err(e) = p.tick() {
+                    eprintln!("Emulation error: {e:?}")
+                } else {
+                    println!("{p}");
+                    print_stop_reason(&p);
+                }
+            }
+            "u" | "undo" => {
+                let count = match input.get(1).map(|c| c.parse::<usize>()) {
+                    Some(Ok(count)) => count,
+                    Some(Err(_)) => {
                         eprintln!("Argument error");
                         continue;
                     }
-                    if load::load_from_file(&mut p, input[1].as_str()) {
+                    None => 1,
+                };
+                let undone = p.undo(count);
+                println!("{p}");
+                if undone < count {
+                    eprintln!("Undo history exhausted after {undone} instruction(s)");
+                }
+            }
+            "rc" | "reverse-continue" => {
+                p.reverse_continue();
+                println!("{p}");
+                print_stop_reason(&p);
+                if p.history_len() == 0 {
+                    eprintln!("Undo history exhausted");
+                }
+            }
+            "rl" | "reverse-to-label" => {
+                if input.len() > 2 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                match p.reverse_to_label(input.get(1).map(String::as_str)) {
+                    Some(_) => {
                         println!("{p}");
+                        if p.history_len() == 0 {
+                            eprintln!("Undo history exhausted");
+                        }
                     }
+                    None => eprintln!("Label '{}' is not defined", input[1]),
+                }
+            }
+            "hl" | "history-limit" => {
+                if input.len() != 2 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                match input[1].parse::<usize>() {
+                    Ok(limit) => {
+                        p.set_history_limit(limit);
+                        println!("History limit: {}", p.history_limit());
+                    }
+                    Err(_) => eprintln!("Argument error"),
+                }
+            }
+            "sl" | "step-limit" => {
+                match input.get(1).map(String::as_str) {
+                    None => {}
+                    Some("off") => p.set_step_limit(None),
+                    Some(limit) => match limit.parse::<usize>() {
+                        Ok(limit) => p.set_step_limit(Some(limit)),
+                        Err(_) => {
+                            eprintln!("Argument error");
+                            continue;
+                        }
+                    },
                 }
-                "d" | "radix" => {
-                    if input.len() != 2 {
+                match p.step_limit() {
+                    Some(limit) => println!("Step limit: {limit}"),
+                    None => println!("Step limit: off"),
+                }
+            }
+            "lo" | "loop-detection" => {
+                match input.get(1).map(String::as_str) {
+                    None => {}
+                    Some("on") => p.set_loop_detection(true),
+                    Some("off") => p.set_loop_detection(false),
+                    Some(_) => {
                         eprintln!("Argument error");
                         continue;
                     }
-                    use processor::{DisplayRadix, DisplaySigned};
-                    match input[1].as_str() {
-                        "u" => p.set_radix(DisplayRadix::Decimal(DisplaySigned::Unsigned)),
-                        "s" => p.set_radix(DisplayRadix::Decimal(DisplaySigned::Signed)),
-                        "x" => p.set_radix(DisplayRadix::Hexadecimal),
-                        "b" => p.set_radix(DisplayRadix::Binary),
-                        _ => println!("Argument error"),
+                }
+                match p.loop_detection() {
+                    true => println!("Loop detection: on"),
+                    false => println!("Loop detection: off"),
+                }
+            }
+            "rh" | "run-history" => {
+                match input.get(1).map(String::as_str) {
+                    None => {}
+                    Some("on") => run_history = true,
+                    Some("off") => run_history = false,
+                    Some(_) => {
+                        eprintln!("Argument error");
+                        continue;
                     }
-                    println!("{p}");
                 }
-                "r" | "run" => {
-                    if let Err(e) = p.run(true, None) {
-                        eprintln!("Emulation error: {e:?}")
-                    } else {
-                        println!("{p}");
+                match run_history {
+                    true => println!("Run history: on"),
+                    false => println!("Run history: off"),
+                }
+            }
+            "b" | "breakpoint" => {
+                if input.len() < 2 || (input.len() > 2 && input[2] != "if") {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                let Some(line) = resolve_line(&p, &input[1]) else {
+                    continue;
+                };
+                if input.len() > 2 {
+                    match parse_expression(&input[3..].join(" ")) {
+                        Ok(condition) => {
+                            if p.is_logpoint(line) {
+                                eprintln!("Replacing logpoint on line {line}");
+                            }
+                            p.set_breakpoint(line, Some(condition))
+                        }
+                        Err(e) => {
+                            eprintln!("{e}");
+                            continue;
+                        }
+                    };
+                } else {
+                    if p.is_logpoint(line) {
+                        eprintln!("Removing logpoint on line {line}");
                     }
+                    p.toggle_breakpoint(line);
+                }
+                println!("{p}");
+            }
+            "lp" | "logpoint" => {
+                if input.len() < 3 {
+                    eprintln!("Argument error");
+                    continue;
                 }
-                "ra" | "run-all" => {
-                    if let Err(e) = p.run(false, None) {
-                        eprintln!("Emulation error: {e:?}")
-                    } else {
+                let Some(line) = resolve_line(&p, &input[1]) else {
+                    continue;
+                };
+                let text = input[2..].join(" ");
+                let (format, condition) =
+                    match text.strip_prefix('"').and_then(|t| t.split_once('"')) {
+                        Some((format, rest)) => match rest.trim().strip_prefix("if ") {
+                            Some(condition) => (format, Some(condition)),
+                            None if rest.trim().is_empty() => (format, None),
+                            None => {
+                                eprintln!("Argument error");
+                                continue;
+                            }
+                        },
+                        None => (text.as_str(), None),
+                    };
+                let condition = match condition.map(parse_expression).transpose() {
+                    Ok(condition) => condition,
+                    Err(e) => {
+                        eprintln!("{e}");
+                        continue;
+                    }
+                };
+                match parse_log_format(format) {
+                    Ok(format) => {
+                        p.set_logpoint(line, format, condition);
                         println!("{p}");
                     }
+                    Err(e) => eprintln!("{e}"),
                 }
-                "s" | "step" | "" => {
-                    if let Err(e) = p.tick() {
-                        eprintln!("Emulation error: {e:?}")
-                    } else {
-                        println!("{p}");
+            }
+            "bi" | "breakpoint-ignore" => {
+                if input.len() != 3 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                let Some(line) = resolve_line(&p, &input[1]) else {
+                    continue;
+                };
+                match input[2].parse::<usize>() {
+                    Ok(count) => {
+                        if p.set_breakpoint_ignore(line, count) {
+                            println!("Breakpoint at {line} will ignore next {count} hit(s)");
+                        } else {
+                            eprintln!("No breakpoint on line {line}");
+                        }
                     }
+                    Err(_) => eprintln!("Argument error"),
                 }
-                "u" | "undo" => {
-                    if let Err(e) = p.run(false, Some(-1)) {
-                        eprintln!("Emulation error: {e:?}")
-                    } else {
-                        println!("{p}");
+            }
+            "bl" | "breakpoint-list" => {
+                if p.breakpoints().next().is_none() {
+                    println!("No breakpoints");
+                }
+                for (line, breakpoint) in p.breakpoints() {
+                    println!("{line:#3} | {breakpoint}");
+                }
+            }
+            "bc" | "breakpoint-clear" => {
+                p.clear_breakpoints();
+                println!("{p}");
+            }
+            "wp" | "watchpoint" => {
+                if input.len() != 3 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                match (input[1].parse::<usize>(), input[2].parse::<WatchKind>()) {
+                    (Ok(addr), Ok(kind)) => {
+                        if p.add_watchpoint(addr, kind) {
+                            println!("{p}");
+                        } else {
+                            eprintln!("Address {addr} is out of range");
+                        }
                     }
+                    _ => eprintln!("Argument error"),
+                }
+            }
+            "wl" | "watchpoint-list" => {
+                if p.watchpoints().is_empty()
+                    && p.register_watches().is_empty()
+                    && p.flag_watches().is_empty()
+                {
+                    println!("No watchpoints");
+                }
+                for watchpoint in p.watchpoints() {
+                    println!("{watchpoint}");
+                }
+                for watch in p.register_watches() {
+                    println!("{watch}");
+                }
+                for flag in p.flag_watches() {
+                    println!("{flag} on flip");
+                }
+            }
+            "wd" | "watchpoint-delete" => {
+                if !(2..=3).contains(&input.len()) {
+                    eprintln!("Argument error");
+                    continue;
                 }
-                "b" | "breakpoint" => {
-                    if input.len() != 2 {
+                let kind = match input.get(2).map(|k| k.parse::<WatchKind>()) {
+                    Some(Ok(kind)) => Some(kind),
+                    Some(Err(_)) => {
                         eprintln!("Argument error");
                         continue;
                     }
-                    let line: usize = input[1].parse().expect("Line parsing error");
-                    p.toggle_breakpoint(line);
-                    println!("{p}");
+                    None => None,
+                };
+                match input[1].parse::<usize>() {
+                    Ok(addr) => {
+                        if p.remove_watchpoint(addr, kind) {
+                            println!("{p}");
+                        } else {
+                            eprintln!("No watchpoint on address {addr}");
+                        }
+                    }
+                    Err(_) => eprintln!("Argument error"),
                 }
-                "bc" | "breakpoint-clear" => {
-                    p.clear_breakpoints();
-                    println!("{p}");
+            }
+            "rw" | "register-watch" => {
+                if input.len() != 3 {
+                    eprintln!("Argument error");
+                    continue;
                 }
-                "j" | "jump" => {
-                    if input.len() != 2 {
+                match (
+                    parse_register(&input[1]),
+                    input[2].parse::<RegisterWatchKind>(),
+                ) {
+                    (Some(register), Ok(kind)) => {
+                        if p.add_register_watch(register, kind) {
+                            println!("{p}");
+                        } else {
+                            eprintln!("Register R{register} does not exist");
+                        }
+                    }
+                    _ => eprintln!("Argument error"),
+                }
+            }
+            "rd" | "register-watch-delete" => {
+                if !(2..=3).contains(&input.len()) {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                let kind = match input.get(2).map(|k| k.parse::<RegisterWatchKind>()) {
+                    Some(Ok(kind)) => Some(kind),
+                    Some(Err(_)) => {
                         eprintln!("Argument error");
                         continue;
                     }
-                    let line: usize = input[1].parse().expect("Line parsing error");
-                    p.program_counter_jump(line);
-                    println!("{p}");
+                    None => None,
+                };
+                match parse_register(&input[1]) {
+                    Some(register) => {
+                        if p.remove_register_watch(register, kind) {
+                            println!("{p}");
+                        } else {
+                            eprintln!("No watch on register R{register}");
+                        }
+                    }
+                    None => eprintln!("Argument error"),
                 }
-                "x" | "reset" => {
-                    p.reset();
+            }
+            "fw" | "flag-watch" => {
+                let Some(Ok(flag)) = input.get(1).map(|f| f.parse::<Flag>()) else {
+                    eprintln!("Argument error");
+                    continue;
+                };
+                println!("{p}");
+                if p.toggle_flag_watch(flag) {
+                    println!("Watching {flag} flag");
+                } else {
+                    println!("Stopped watching {flag} flag");
+                }
+            }
+            "wc" | "watchpoint-clear" => {
+                p.clear_all_watches();
+                println!("{p}");
+            }
+            "pa" | "peripheral-add" => {
+                if input.len() < 3 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                let args = input[3..].iter().map(String::as_str).collect::<Vec<_>>();
+                let peripheral = input[2]
+                    .parse()
+                    .ok()
+                    .and_then(|addr| peripheral::create(&input[1], addr, &args));
+                let Some(peripheral) = peripheral else {
+                    eprintln!("Argument error");
+                    continue;
+                };
+                let range = peripheral.range();
+                if p.add_peripheral(peripheral) {
                     println!("{p}");
+                } else {
+                    eprintln!(
+                        "Addresses {}..{} are out of range or already mapped",
+                        range.start, range.end
+                    );
+                }
+            }
+            "pl" | "peripheral-list" => {
+                if p.peripherals().next().is_none() {
+                    println!("No peripherals");
+                }
+                for peripheral in p.peripherals() {
+                    let range = peripheral.range();
+                    println!("{} at {}..{}", peripheral.name(), range.start, range.end);
+                }
+            }
+            "pd" | "peripheral-delete" => {
+                let Some(Ok(addr)) = input.get(1).map(|a| a.parse::<usize>()) else {
+                    eprintln!("Argument error");
+                    continue;
+                };
+                match p.remove_peripheral(addr) {
+                    Some(_) => println!("{p}"),
+                    None => eprintln!("No peripheral at address {addr}"),
+                }
+            }
+            "sw" | "switch" => {
+                if input.len() != 3 {
+                    eprintln!("Argument error");
+                    continue;
                 }
-                "e" | "benchmark" => {
-                    p.load_rom(asm::BENCHMARK);
-                    p.reset();
-                    let stopwatch = std::time::Instant::now();
-                    match p.run(false, None) {
-                        Ok(ticks) => println!(
+                let (Ok(addr), Ok(index)) = (input[1].parse(), input[2].parse()) else {
+                    eprintln!("Argument error");
+                    continue;
+                };
+                match p
+                    .peripheral_as_mut::<Switches>(addr)
+                    .map(|s| s.toggle(index))
+                {
+                    Some(true) => println!("{p}"),
+                    Some(false) => eprintln!("No switch {index} at address {addr}"),
+                    None => eprintln!("No switches at address {addr}"),
+                }
+            }
+            "ui" | "uart-input" | "uf" | "uart-file" => {
+                if input.len() < 2 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                let text = match input[0].as_str() {
+                    "ui" | "uart-input" => input[1..].join(" ") + "\n",
+                    _ => match std::fs::read_to_string(&input[1]) {
+                        Ok(text) => text,
+                        Err(e) => {
+                            eprintln!("Filesystem error: {e}");
+                            continue;
+                        }
+                    },
+                };
+                match p.find_peripheral_mut::<Uart>() {
+                    Some(uart) => {
+                        uart.queue(&text);
+                        println!("{p}");
+                    }
+                    None => eprintln!("No console peripheral"),
+                }
+            }
+            "j" | "jump" => {
+                if input.len() != 2 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                let Some(line) = resolve_line(&p, &input[1]) else {
+                    continue;
+                };
+                p.program_counter_jump(line);
+                println!("{p}");
+            }
+            "x" | "reset" => {
+                p.reset();
+                println!("{p}");
+            }
+            "e" | "benchmark" => {
+                let frequency = match input.get(1).map(|f| parse_frequency(f)) {
+                    Some(None) => {
+                        eprintln!("Argument error");
+                        continue;
+                    }
+                    Some(Some(frequency)) => Some(frequency),
+                    None => None,
+                };
+                p.load_rom(asm::BENCHMARK);
+                p.reset();
+                let stopwatch = std::time::Instant::now();
+                match run_all(&mut p, None, false) {
+                    outcome @ RunOutcome::Error(_) => print_outcome(&p, outcome),
+                    outcome => {
+                        let ticks = p.runtime_counter();
+                        println!(
                             "Emulation speed: {:.2} MIPS",
                             ticks as f64 / stopwatch.elapsed().as_secs_f64() / 1e6
-                        ),
-                        Err(e) => eprintln!("Emulation error: {e:?}"),
+                        );
+                        println!("Clock cycles: {}", p.cycle_counter());
+                        if let Some(frequency) = frequency {
+                            println!(
+                                "Simulated time at {} Hz: {:.6} s",
+                                frequency,
+                                p.simulated_time(frequency)
+                            );
+                        }
+                        print_outcome(&p, outcome);
+                    }
+                }
+            }
+            "cy" | "cycles" => {
+                if input.len() == 1 {
+                    println!("Cycles per instruction: {}", p.cycle_model());
+                    continue;
+                }
+                if input.len() != 3 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                let (Ok(class), Ok(cycles)) = (input[1].parse(), input[2].parse()) else {
+                    eprintln!("Argument error");
+                    continue;
+                };
+                let mut model = p.cycle_model();
+                model.set(class, cycles);
+                p.set_cycle_model(model);
+                println!("Cycles per instruction: {model}");
+            }
+            "w" | "watch" => {
+                if input.len() > 2 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                let rerun = match input.get(1).map(String::as_str) {
+                    Some("off") => {
+                        watch = None;
+                        println!("Stopped watching");
+                        continue;
+                    }
+                    Some("rerun") => true,
+                    Some(_) => {
+                        eprintln!("Argument error");
+                        continue;
+                    }
+                    None => false,
+                };
+                match &path {
+                    Some(path) => {
+                        watch = Some(Watch {
+                            watcher: FileWatcher::new(path),
+                            rerun,
+                        });
+                        println!("Watching '{path}'");
                     }
+                    None => eprintln!("No file is loaded"),
+                }
+            }
+            "pf" | "profile" => print!("{}", p.profile()),
+            "cv" | "coverage" => {
+                if input.len() > 2 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                let coverage = p.coverage();
+                match input.get(1) {
+                    Some(path) => match std::fs::write(path, coverage.to_json() + "\n") {
+                        Ok(()) => println!("Coverage summary written to '{path}'"),
+                        Err(e) => eprintln!("Coverage error: {e}"),
+                    },
+                    None => print!("{coverage}"),
+                }
+            }
+            "t" | "trace" => {
+                if input.len() < 2 || input.len() > 3 {
+                    eprintln!("Argument error");
+                    continue;
+                }
+                if input[1] == "off" {
+                    match p.stop_trace() {
+                        Ok(true) => println!("Stopped tracing"),
+                        Ok(false) => eprintln!("No trace is being recorded"),
+                        Err(e) => eprintln!("Trace error: {e}"),
+                    }
+                    continue;
+                }
+                let format = match input.get(2) {
+                    Some(format) => format.parse(),
+                    None if input[1].ends_with(".csv") => Ok(TraceFormat::Csv),
+                    None => Ok(TraceFormat::JsonLines),
+                };
+                let Ok(format) = format else {
+                    eprintln!("Argument error");
+                    continue;
+                };
+                let trace = File::create(&input[1])
+                    .and_then(|file| p.start_trace(Box::new(BufWriter::new(file)), format));
+                match trace {
+                    Ok(()) => println!("Tracing to '{}'", input[1]),
+                    Err(e) => eprintln!("Trace error: {e}"),
                 }
-                "h" | "help" => print_help(),
-                _ => eprintln!("Command error"),
             }
-        } else {
-            eprintln!("Input error");
+            "h" | "help" => print_help(),
+            _ => eprintln!("Command error"),
         }
     }
+    ExitCode::SUCCESS
 }
@@ -1,23 +1,37 @@
+//! Assembly files and the debugger expressions, locations and logpoint
+//! messages typed at the prompt.
+
 use pest::iterators::Pair;
 use pest::Parser;
 
 use crate::instructions::Instruction;
-use crate::processor::{RAM_SIZE, ROM_SIZE};
+use crate::processor::MachineConfig;
 
 use std::collections::HashMap;
 
 mod data;
 mod error;
+mod expression;
+mod location;
+mod log_format;
 mod text;
 
 use data::parse_data;
-use error::ParsingError;
+pub use error::ParsingError;
+pub use expression::{parse_expression, Expression, Operator};
+pub use location::{parse_location, Location};
+pub use log_format::{parse_log_format, LogFormat, LogSegment};
 use text::{parse_instructions, Labels, RawInstruction, RawInstructions};
 
-#[derive(Parser)]
-#[grammar = "src/parser/isa.pest"]
-pub struct AsmFileParser;
+mod grammar {
+    #[derive(Parser)]
+    #[grammar = "src/parser/isa.pest"]
+    pub struct AsmFileParser;
+}
+
+use grammar::{AsmFileParser, Rule};
 
+/// Program memory, data memory and labels indexed by address.
 pub type AsmFileData = (Vec<Instruction>, Vec<u16>, HashMap<usize, Vec<String>>);
 
 struct AsmFile<'a> {
@@ -43,7 +57,13 @@ impl From<&str> for ProgramSection {
     }
 }
 
+/// Parses an assembly file into its program, data and labels.
 pub fn parse_file(path: &str) -> Result<AsmFileData, ParsingError> {
+    parse_file_with(path, &MachineConfig::default())
+}
+
+/// Parses an assembly file for a machine with the given memory sizes.
+pub fn parse_file_with(path: &str, config: &MachineConfig) -> Result<AsmFileData, ParsingError> {
     let text = std::fs::read_to_string(path)?;
     let file = AsmFileParser::parse(Rule::file, &text)?.next();
     let pairs;
@@ -53,9 +73,9 @@ pub fn parse_file(path: &str) -> Result<AsmFileData, ParsingError> {
         return Err(ParsingError::MalformedFile);
     }
     let mut asmfile = AsmFile {
-        data: Vec::with_capacity(RAM_SIZE),
-        instructions: Vec::with_capacity(ROM_SIZE),
-        labels: HashMap::with_capacity(ROM_SIZE),
+        data: Vec::with_capacity(config.ram_size()),
+        instructions: Vec::with_capacity(config.rom_size()),
+        labels: HashMap::with_capacity(config.rom_size()),
     };
     let mut current_section: Option<ProgramSection> = None;
     for line in pairs.into_inner() {
@@ -101,6 +121,20 @@ pub fn parse_file(path: &str) -> Result<AsmFileData, ParsingError> {
             _ => {}
         }
     }
+    if asmfile.instructions.len() > config.rom_size() {
+        return Err(ParsingError::SectionOverflow(
+            "text".into(),
+            asmfile.instructions.len(),
+            config.rom_size(),
+        ));
+    }
+    if asmfile.data.len() > config.ram_size() {
+        return Err(ParsingError::SectionOverflow(
+            "data".into(),
+            asmfile.data.len(),
+            config.ram_size(),
+        ));
+    }
     let instructions = parse_instructions(&asmfile.instructions, &asmfile.labels)?;
     let mut labels: HashMap<usize, Vec<String>> = HashMap::with_capacity(asmfile.labels.len());
     for (k, v) in &asmfile.labels {
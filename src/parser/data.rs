@@ -1,6 +1,6 @@
 use super::{Pair, ParsingError, Rule};
 
-fn parse_radix(pair: Pair<'_, Rule>) -> Result<u16, ParsingError> {
+pub fn parse_radix(pair: Pair<'_, Rule>) -> Result<u16, ParsingError> {
     let data = pair.as_span().as_str();
     if data.len() < 3 {
         return Err(ParsingError::UnexpectedToken);
@@ -1,22 +1,37 @@
 use super::Rule;
 use std::fmt::Display;
 
+/// Reason an assembly file, expression, location or log format could not be
+/// parsed.
 #[derive(Debug)]
 pub enum ParsingError {
+    /// File could not be read.
     Filesystem(std::io::Error),
+    /// Text does not match the grammar.
     Pest(Box<pest::error::Error<Rule>>),
+    /// Label is used but never defined.
     UndefinedLabel(String),
+    /// Label is defined more than once.
     RedefinedLabel(String),
+    /// Number does not fit its type.
     NumberConversion(String),
+    /// Instruction or data appears outside its section.
     WrongSection(String),
+    /// Address is outside the memory it refers to.
+    InvalidAddress(String),
+    /// Section holds more entries than its memory, with the section name,
+    /// its size and the capacity.
+    SectionOverflow(String, usize, usize),
+    /// Grammar produced a token where it was not expected.
     UnexpectedToken,
+    /// Grammar did not produce an expected token.
     MissingToken,
+    /// File produced no parse tree.
     MalformedFile,
 }
 
 impl From<std::io::Error> for ParsingError {
     fn from(e: std::io::Error) -> Self {
-        println!("{e}");
         Self::Filesystem(e)
     }
 }
@@ -39,6 +54,9 @@ impl Display for ParsingError {
                 Self::RedefinedLabel(label) => format!("Label '{label}' is defined multiple times"),
                 Self::NumberConversion(value) => format!("Unable to convert '{value}' to a number"),
                 Self::WrongSection(value) => format!("Found {value} outside its section"),
+                Self::InvalidAddress(value) => format!("Address '{value}' is out of range"),
+                Self::SectionOverflow(section, size, capacity) =>
+                    format!("Section {section} has {size} entries but memory holds only {capacity}"),
                 e => format!("{e:?}"),
             }
         )
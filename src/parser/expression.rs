@@ -0,0 +1,292 @@
+use pest::iterators::{Pair, Pairs};
+use pest::pratt_parser::{Assoc, Op, PrattParser};
+use pest::Parser;
+
+use super::data::parse_radix;
+use super::{AsmFileParser, ParsingError, Rule};
+use crate::processor::Flag;
+
+use std::fmt::Display;
+
+/// Binary operator of an [`Expression`], from lowest to highest precedence.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+    /// `||`
+    Or,
+    /// `&&`
+    And,
+    /// `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`
+    Less,
+    /// `<=`
+    LessEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterEqual,
+    /// `+`
+    Add,
+    /// `-`
+    Subtract,
+}
+
+/// Debugger expression evaluated against the processor state, such as
+/// `R2 == 0 && mem[5] > 10`.
+///
+/// Registers, memory cells and hexadecimal or binary literals are read as
+/// signed 16-bit words, while comparisons and logical operators evaluate
+/// to `1` or `0`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    /// Number, such as `10`, `0x1f` or `0b101`.
+    Literal(i64),
+    /// Register such as `R2` or `r2`.
+    Register(u8),
+    /// Flag `zero`, `sign` or `carry`.
+    Flag(Flag),
+    /// Data memory cell at a computed address, `mem[...]`.
+    Memory(Box<Expression>),
+    /// `pc`
+    ProgramCounter,
+    /// `runtime`, the number of executed instructions.
+    RuntimeCounter,
+    /// `!`, which is `1` for a zero operand and `0` otherwise.
+    Not(Box<Expression>),
+    /// Unary `-`.
+    Negate(Box<Expression>),
+    /// Operator applied to two operands.
+    Binary(Operator, Box<Expression>, Box<Expression>),
+}
+
+fn pratt_parser() -> PrattParser<Rule> {
+    PrattParser::new()
+        .op(Op::infix(Rule::logic_or, Assoc::Left))
+        .op(Op::infix(Rule::logic_and, Assoc::Left))
+        .op(Op::infix(Rule::equal, Assoc::Left) | Op::infix(Rule::not_equal, Assoc::Left))
+        .op(Op::infix(Rule::less, Assoc::Left)
+            | Op::infix(Rule::less_equal, Assoc::Left)
+            | Op::infix(Rule::greater, Assoc::Left)
+            | Op::infix(Rule::greater_equal, Assoc::Left))
+        .op(Op::infix(Rule::plus, Assoc::Left) | Op::infix(Rule::minus, Assoc::Left))
+        .op(Op::prefix(Rule::not) | Op::prefix(Rule::negative))
+}
+
+fn parse_operand(
+    pair: Pair<'_, Rule>,
+    pratt: &PrattParser<Rule>,
+) -> Result<Expression, ParsingError> {
+    match pair.as_rule() {
+        Rule::literal => {
+            let literal = pair.into_inner().next().ok_or(ParsingError::MissingToken)?;
+            match literal.as_rule() {
+                Rule::radix => Ok(Expression::Literal(parse_radix(literal)? as i16 as i64)),
+                _ => match literal.as_str().parse::<i64>() {
+                    Ok(value) => Ok(Expression::Literal(value)),
+                    Err(_) => Err(ParsingError::NumberConversion(literal.as_str().into())),
+                },
+            }
+        }
+        Rule::any_register => {
+            let index = pair.into_inner().next().ok_or(ParsingError::MissingToken)?;
+            match index.as_str().parse::<u8>() {
+                Ok(index) => Ok(Expression::Register(index)),
+                Err(_) => Err(ParsingError::NumberConversion(index.as_str().into())),
+            }
+        }
+        Rule::flag => match pair.as_str().parse::<Flag>() {
+            Ok(flag) => Ok(Expression::Flag(flag)),
+            Err(_) => Err(ParsingError::UnexpectedToken),
+        },
+        Rule::counter => match pair.as_str() {
+            "pc" => Ok(Expression::ProgramCounter),
+            "runtime" => Ok(Expression::RuntimeCounter),
+            _ => Err(ParsingError::UnexpectedToken),
+        },
+        Rule::memory => {
+            let address = pair.into_inner().next().ok_or(ParsingError::MissingToken)?;
+            Ok(Expression::Memory(Box::new(parse_pairs(
+                address.into_inner(),
+                pratt,
+            )?)))
+        }
+        Rule::expression => parse_pairs(pair.into_inner(), pratt),
+        _ => Err(ParsingError::UnexpectedToken),
+    }
+}
+
+fn parse_pairs(
+    pairs: Pairs<'_, Rule>,
+    pratt: &PrattParser<Rule>,
+) -> Result<Expression, ParsingError> {
+    pratt
+        .map_primary(|primary| parse_operand(primary, pratt))
+        .map_prefix(|op, rhs| match op.as_rule() {
+            Rule::not => Ok(Expression::Not(Box::new(rhs?))),
+            Rule::negative => Ok(Expression::Negate(Box::new(rhs?))),
+            _ => Err(ParsingError::UnexpectedToken),
+        })
+        .map_infix(|lhs, op, rhs| {
+            let op = match op.as_rule() {
+                Rule::logic_or => Operator::Or,
+                Rule::logic_and => Operator::And,
+                Rule::equal => Operator::Equal,
+                Rule::not_equal => Operator::NotEqual,
+                Rule::less => Operator::Less,
+                Rule::less_equal => Operator::LessEqual,
+                Rule::greater => Operator::Greater,
+                Rule::greater_equal => Operator::GreaterEqual,
+                Rule::plus => Operator::Add,
+                Rule::minus => Operator::Subtract,
+                _ => return Err(ParsingError::UnexpectedToken),
+            };
+            Ok(Expression::Binary(op, Box::new(lhs?), Box::new(rhs?)))
+        })
+        .parse(pairs)
+}
+
+/// Parses a debugger expression such as `R2 == 0 && mem[5] > 10`.
+pub fn parse_expression(text: &str) -> Result<Expression, ParsingError> {
+    let condition = AsmFileParser::parse(Rule::condition, text)?.next();
+    match condition.and_then(|c| c.into_inner().next()) {
+        Some(expression) => parse_pairs(expression.into_inner(), &pratt_parser()),
+        None => Err(ParsingError::MissingToken),
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Or => write!(f, "||"),
+            Self::And => write!(f, "&&"),
+            Self::Equal => write!(f, "=="),
+            Self::NotEqual => write!(f, "!="),
+            Self::Less => write!(f, "<"),
+            Self::LessEqual => write!(f, "<="),
+            Self::Greater => write!(f, ">"),
+            Self::GreaterEqual => write!(f, ">="),
+            Self::Add => write!(f, "+"),
+            Self::Subtract => write!(f, "-"),
+        }
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Literal(value) => write!(f, "{value}"),
+            Self::Register(index) => write!(f, "R{index}"),
+            Self::Flag(flag) => write!(f, "{flag}"),
+            Self::Memory(address) => write!(f, "mem[{address}]"),
+            Self::ProgramCounter => write!(f, "pc"),
+            Self::RuntimeCounter => write!(f, "runtime"),
+            Self::Not(e) => {
+                write!(f, "!")?;
+                write_operand(f, e)
+            }
+            Self::Negate(e) => {
+                write!(f, "-")?;
+                write_operand(f, e)
+            }
+            Self::Binary(op, lhs, rhs) => {
+                write_operand(f, lhs)?;
+                write!(f, " {op} ")?;
+                write_operand(f, rhs)
+            }
+        }
+    }
+}
+
+fn write_operand(f: &mut std::fmt::Formatter<'_>, e: &Expression) -> std::fmt::Result {
+    match e {
+        Expression::Binary(..) => write!(f, "({e})"),
+        _ => write!(f, "{e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_expression, Expression, Operator};
+    use crate::parser::ParsingError;
+    use crate::processor::Flag;
+
+    fn binary(op: Operator, lhs: Expression, rhs: Expression) -> Expression {
+        Expression::Binary(op, Box::new(lhs), Box::new(rhs))
+    }
+
+    #[test]
+    fn operators_follow_precedence() {
+        assert_eq!(
+            parse_expression("R2 == 0 && mem[5] > 10 || carry").unwrap(),
+            binary(
+                Operator::Or,
+                binary(
+                    Operator::And,
+                    binary(
+                        Operator::Equal,
+                        Expression::Register(2),
+                        Expression::Literal(0)
+                    ),
+                    binary(
+                        Operator::Greater,
+                        Expression::Memory(Box::new(Expression::Literal(5))),
+                        Expression::Literal(10)
+                    )
+                ),
+                Expression::Flag(Flag::Carry)
+            )
+        );
+    }
+
+    #[test]
+    fn arithmetic_binds_tighter_than_comparison() {
+        let expression = parse_expression("pc + 1 < -runtime").unwrap();
+        assert_eq!(
+            expression,
+            binary(
+                Operator::Less,
+                binary(
+                    Operator::Add,
+                    Expression::ProgramCounter,
+                    Expression::Literal(1)
+                ),
+                Expression::Negate(Box::new(Expression::RuntimeCounter))
+            )
+        );
+        assert_eq!(expression.to_string(), "(pc + 1) < -runtime");
+    }
+
+    #[test]
+    fn registers_accept_either_case() {
+        assert_eq!(parse_expression("r2").unwrap(), Expression::Register(2));
+        assert_eq!(parse_expression("R2").unwrap(), Expression::Register(2));
+    }
+
+    #[test]
+    fn radix_literals_are_signed_words() {
+        assert_eq!(parse_expression("0xffff").unwrap(), Expression::Literal(-1));
+        assert_eq!(parse_expression("0b101").unwrap(), Expression::Literal(5));
+        assert_eq!(
+            parse_expression("65535").unwrap(),
+            Expression::Literal(65535)
+        );
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        assert!(matches!(
+            parse_expression("R2 =="),
+            Err(ParsingError::Pest(_))
+        ));
+        assert!(matches!(
+            parse_expression("mem[1"),
+            Err(ParsingError::Pest(_))
+        ));
+        assert!(matches!(
+            parse_expression("R300"),
+            Err(ParsingError::NumberConversion(_))
+        ));
+    }
+}
@@ -0,0 +1,143 @@
+use pest::Parser;
+
+use super::{AsmFileParser, ParsingError, Rule};
+
+use std::fmt::Display;
+
+/// Program memory address given either directly or relative to a label,
+/// such as `12`, `loop` or `loop+2`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Location {
+    /// Absolute address.
+    Address(usize),
+    /// Label with an offset in instructions.
+    Label(String, isize),
+}
+
+/// Parses a program memory location such as `12`, `loop` or `loop+2`.
+pub fn parse_location(text: &str) -> Result<Location, ParsingError> {
+    let location = AsmFileParser::parse(Rule::location, text)?.next();
+    let mut pairs = match location {
+        Some(location) => location.into_inner(),
+        None => return Err(ParsingError::MissingToken),
+    };
+    match pairs.next() {
+        Some(address) if address.as_rule() == Rule::number => match address.as_str().parse() {
+            Ok(address) => Ok(Location::Address(address)),
+            Err(_) => Err(ParsingError::NumberConversion(address.as_str().into())),
+        },
+        Some(label) => {
+            let offset = match pairs.next().filter(|p| p.as_rule() == Rule::offset) {
+                Some(offset) => match offset
+                    .as_str()
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect::<String>()
+                    .parse()
+                {
+                    Ok(offset) => offset,
+                    Err(_) => return Err(ParsingError::NumberConversion(offset.as_str().into())),
+                },
+                None => 0,
+            };
+            Ok(Location::Label(label.as_str().into(), offset))
+        }
+        None => Err(ParsingError::MissingToken),
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Address(address) => write!(f, "{address}"),
+            Self::Label(label, 0) => write!(f, "{label}"),
+            Self::Label(label, offset) => write!(f, "{label}{offset:+}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{parse_location, Location};
+    use crate::parser::ParsingError;
+    use crate::processor::Processor;
+
+    fn processor() -> Processor {
+        let mut p = Processor::new();
+        p.load_labels(HashMap::from([
+            (1, vec!["loop".into()]),
+            (3, vec!["end".into()]),
+        ]));
+        p
+    }
+
+    fn resolve(p: &Processor, text: &str) -> Result<usize, ParsingError> {
+        p.resolve(&parse_location(text)?)
+    }
+
+    #[test]
+    fn addresses_labels_and_offsets_are_parsed() {
+        assert_eq!(parse_location("12").unwrap(), Location::Address(12));
+        assert_eq!(
+            parse_location("loop").unwrap(),
+            Location::Label("loop".into(), 0)
+        );
+        assert_eq!(
+            parse_location("loop + 2").unwrap(),
+            Location::Label("loop".into(), 2)
+        );
+        assert_eq!(
+            parse_location("end-1").unwrap(),
+            Location::Label("end".into(), -1)
+        );
+        assert!(parse_location("loop+").is_err());
+        assert!(parse_location("").is_err());
+    }
+
+    #[test]
+    fn locations_display_as_parsed() {
+        for text in ["12", "loop", "loop+2", "end-1"] {
+            assert_eq!(parse_location(text).unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn labels_resolve_with_offsets() {
+        let p = processor();
+        assert_eq!(resolve(&p, "loop").unwrap(), 1);
+        assert_eq!(resolve(&p, "loop+2").unwrap(), 3);
+        assert_eq!(resolve(&p, "end-3").unwrap(), 0);
+        assert_eq!(resolve(&p, "end+252").unwrap(), 255);
+    }
+
+    #[test]
+    fn locations_outside_program_memory_are_rejected() {
+        let p = processor();
+        assert!(matches!(
+            resolve(&p, "end-4"),
+            Err(ParsingError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            resolve(&p, "end+253"),
+            Err(ParsingError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            resolve(&p, "256"),
+            Err(ParsingError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            resolve(&p, "missing+1"),
+            Err(ParsingError::UndefinedLabel(_))
+        ));
+    }
+
+    #[test]
+    fn addresses_symbolize_to_the_preceding_label() {
+        let p = processor();
+        assert_eq!(p.symbolize(0), Location::Address(0));
+        assert_eq!(p.symbolize(2), Location::Label("loop".into(), 1));
+        assert_eq!(p.symbolize(3), Location::Label("end".into(), 0));
+    }
+}
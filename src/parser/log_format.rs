@@ -0,0 +1,123 @@
+use super::{parse_expression, Expression, ParsingError};
+
+use std::fmt::Display;
+
+/// Logpoint message with debugger expressions in braces, such as
+/// `iter {R2} sum {R0} mem5={mem[5]}`. Literal braces are written as `{{`
+/// and `}}`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogFormat {
+    /// Parts of the message in order.
+    pub segments: Vec<LogSegment>,
+}
+
+/// Part of a [`LogFormat`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum LogSegment {
+    /// Text printed as is.
+    Text(String),
+    /// Expression printed as its value.
+    Expression(Expression),
+}
+
+/// Parses a logpoint message such as `iter {R2} sum {R0}`.
+pub fn parse_log_format(text: &str) -> Result<LogFormat, ParsingError> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let (expression, rest) = chars
+                    .as_str()
+                    .split_once('}')
+                    .ok_or(ParsingError::MissingToken)?;
+                if !literal.is_empty() {
+                    segments.push(LogSegment::Text(std::mem::take(&mut literal)));
+                }
+                segments.push(LogSegment::Expression(parse_expression(expression)?));
+                chars = rest.chars();
+            }
+            '}' => return Err(ParsingError::UnexpectedToken),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(LogSegment::Text(literal));
+    }
+    Ok(LogFormat { segments })
+}
+
+impl Display for LogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for segment in &self.segments {
+            match segment {
+                LogSegment::Text(text) => {
+                    write!(f, "{}", text.replace('{', "{{").replace('}', "}}"))?
+                }
+                LogSegment::Expression(expression) => write!(f, "{{{expression}}}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_log_format, LogSegment};
+    use crate::parser::{parse_expression, ParsingError};
+    use crate::processor::Processor;
+
+    #[test]
+    fn text_and_expressions_are_split() {
+        let format = parse_log_format("iter {R2} sum {r0 + 1}!").unwrap();
+        assert_eq!(
+            format.segments,
+            vec![
+                LogSegment::Text("iter ".into()),
+                LogSegment::Expression(parse_expression("R2").unwrap()),
+                LogSegment::Text(" sum ".into()),
+                LogSegment::Expression(parse_expression("R0 + 1").unwrap()),
+                LogSegment::Text("!".into()),
+            ]
+        );
+        assert_eq!(format.to_string(), "iter {R2} sum {R0 + 1}!");
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        let format = parse_log_format("{{R1}} = {R1}").unwrap();
+        assert_eq!(format.segments[0], LogSegment::Text("{R1} = ".into()));
+        assert_eq!(format.to_string(), "{{R1}} = {R1}");
+    }
+
+    #[test]
+    fn unbalanced_braces_are_rejected() {
+        assert!(matches!(
+            parse_log_format("sum {R0"),
+            Err(ParsingError::MissingToken)
+        ));
+        assert!(matches!(
+            parse_log_format("sum R0}"),
+            Err(ParsingError::UnexpectedToken)
+        ));
+        assert!(parse_log_format("sum {R0 +}").is_err());
+    }
+
+    #[test]
+    fn unevaluable_expressions_are_shown_as_question_marks() {
+        let mut p = Processor::new();
+        p.load_rom(&crate::asm![sreg 1, 7]);
+        p.tick().unwrap();
+        let format = parse_log_format("R1={R1} cell={mem[300]}").unwrap();
+        assert_eq!(p.format_log(&format), "R1=7 cell=?");
+    }
+}
@@ -26,7 +26,7 @@ pub enum RawInstruction<'a> {
 pub type RawInstructions<'a> = Vec<RawInstruction<'a>>;
 pub type Labels<'a> = HashMap<&'a str, usize>;
 
-fn parse_instruction_l(pair: Pair<'_, Rule>) -> Result<RawInstruction, ParsingError> {
+fn parse_instruction_l(pair: Pair<'_, Rule>) -> Result<RawInstruction<'_>, ParsingError> {
     let mut pairs;
     if let Rule::instr_l = pair.as_rule() {
         pairs = pair.into_inner();
@@ -42,7 +42,7 @@ fn parse_instruction_l(pair: Pair<'_, Rule>) -> Result<RawInstruction, ParsingEr
     }
 }
 
-fn parse_instruction_2r(pair: Pair<'_, Rule>) -> Result<RawInstruction, ParsingError> {
+fn parse_instruction_2r(pair: Pair<'_, Rule>) -> Result<RawInstruction<'_>, ParsingError> {
     let mut pairs;
     if let Rule::instr_2r = pair.as_rule() {
         pairs = pair.into_inner();
@@ -67,7 +67,7 @@ fn parse_instruction_2r(pair: Pair<'_, Rule>) -> Result<RawInstruction, ParsingE
     }
 }
 
-fn parse_instruction_3r(pair: Pair<'_, Rule>) -> Result<RawInstruction, ParsingError> {
+fn parse_instruction_3r(pair: Pair<'_, Rule>) -> Result<RawInstruction<'_>, ParsingError> {
     let mut pairs;
     if let Rule::instr_3r = pair.as_rule() {
         pairs = pair.into_inner();
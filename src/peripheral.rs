@@ -0,0 +1,119 @@
+//! Memory-mapped devices attached to the data memory of a [`Processor`].
+//!
+//! [`Processor`]: crate::processor::Processor
+
+use std::any::Any;
+use std::fmt::{Formatter, Result};
+use std::ops::Range;
+
+mod led_matrix;
+mod leds;
+mod seven_segment;
+mod switches;
+mod timer;
+mod uart;
+
+pub use led_matrix::LedMatrix;
+pub use leds::Leds;
+pub use seven_segment::SevenSegment;
+pub use switches::Switches;
+pub use timer::Timer;
+pub use uart::Uart;
+
+/// Allows downcasting a peripheral to its concrete type.
+pub trait AsAny {
+    /// Peripheral as [`Any`].
+    fn as_any(&self) -> &dyn Any;
+    /// Peripheral as mutable [`Any`].
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Device claiming a range of data memory addresses. Loads and stores to
+/// those addresses are handled by the device instead of the memory.
+///
+/// Undoing an instruction restores values stored to the device, but not
+/// other state it changed on reads or ticks.
+pub trait Peripheral: AsAny {
+    /// Kind of the device, shown in listings and the state view.
+    fn name(&self) -> &str;
+
+    /// Data memory addresses handled by the device.
+    fn range(&self) -> Range<usize>;
+
+    /// Value loaded by the program from an address within the range.
+    fn read(&mut self, address: usize) -> u16 {
+        self.peek(address)
+    }
+
+    /// Value at an address within the range, without side effects.
+    fn peek(&self, address: usize) -> u16;
+
+    /// Stores a value written by the program to an address within the range.
+    fn write(&mut self, address: usize, value: u16);
+
+    /// Puts back the value an address had before an undone store.
+    fn restore(&mut self, address: usize, value: u16) {
+        self.write(address, value);
+    }
+
+    /// Called after every executed instruction with the runtime and cycle
+    /// counters of the processor, and with the earlier counters for every
+    /// undone instruction before its store to the device is restored.
+    fn tick(&mut self, _instructions: usize, _cycles: usize) {}
+
+    /// Called with the number of executed instructions the processor can
+    /// undo whenever it changes, so state kept for undoing stores can be
+    /// limited to the same length.
+    fn set_history_limit(&mut self, _limit: usize) {}
+
+    /// Called when the processor discards its undo history, so state kept
+    /// for undoing stores can be dropped with it.
+    fn clear_history(&mut self) {}
+
+    /// Returns the device to its initial state on processor reset.
+    fn reset(&mut self) {}
+
+    /// Draws the device under the data memory in the state view.
+    fn render(&self, _f: &mut Formatter<'_>) -> Result {
+        Ok(())
+    }
+}
+
+/// Creates a peripheral of the given kind at a data memory address, with
+/// optional kind-specific arguments.
+pub fn create(kind: &str, address: usize, args: &[&str]) -> Option<Box<dyn Peripheral>> {
+    match (kind, args) {
+        ("leds", []) => Some(Box::new(Leds::new(address, 8)?)),
+        ("leds", [width]) => Some(Box::new(Leds::new(address, width.parse().ok()?)?)),
+        ("switches", []) => Some(Box::new(Switches::new(address, 8)?)),
+        ("switches", [width]) => Some(Box::new(Switches::new(address, width.parse().ok()?)?)),
+        ("7seg", []) => Some(Box::new(SevenSegment::new(address, 4)?)),
+        ("7seg", [digits]) => Some(Box::new(SevenSegment::new(address, digits.parse().ok()?)?)),
+        ("matrix", []) => Some(Box::new(LedMatrix::new(address, 8)?)),
+        ("matrix", [size]) => Some(Box::new(LedMatrix::new(address, size.parse().ok()?)?)),
+        ("uart", []) => Some(Box::new(Uart::new(address))),
+        ("timer", [] | ["instructions"]) => Some(Box::new(Timer::new(address, false))),
+        ("timer", ["cycles"]) => Some(Box::new(Timer::new(address, true))),
+        _ => None,
+    }
+}
+
+/// Draws the lowest `width` bits of a value, most significant bit first.
+pub(crate) fn bits(value: u16, width: usize, on: char, off: char) -> String {
+    (0..width)
+        .rev()
+        .map(|bit| if value & (1 << bit) != 0 { on } else { off })
+        .map(String::from)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,66 @@
+use std::fmt::{Formatter, Result};
+use std::ops::Range;
+
+use super::{bits, Peripheral};
+
+/// Square matrix of 8x8 or 16x16 LEDs, where each word stored from its
+/// first address on lights one row, most significant bit on the left.
+pub struct LedMatrix {
+    address: usize,
+    rows: Vec<u16>,
+}
+
+impl LedMatrix {
+    /// Creates a matrix of the given size, or `None` unless it is 8 or 16.
+    pub fn new(address: usize, size: usize) -> Option<Self> {
+        match size {
+            8 | 16 => Some(Self {
+                address,
+                rows: vec![0; size],
+            }),
+            _ => None,
+        }
+    }
+
+    /// Lit LEDs of each row, top row first.
+    pub fn rows(&self) -> &[u16] {
+        &self.rows
+    }
+
+    fn mask(&self) -> u16 {
+        (u32::MAX >> (32 - self.rows.len())) as u16
+    }
+}
+
+impl Peripheral for LedMatrix {
+    fn name(&self) -> &str {
+        "matrix"
+    }
+
+    fn range(&self) -> Range<usize> {
+        self.address..self.address + self.rows.len()
+    }
+
+    fn peek(&self, address: usize) -> u16 {
+        self.rows[address - self.address]
+    }
+
+    fn write(&mut self, address: usize, value: u16) {
+        self.rows[address - self.address] = value & self.mask();
+    }
+
+    fn reset(&mut self) {
+        self.rows.iter_mut().for_each(|row| *row = 0);
+    }
+
+    fn render(&self, f: &mut Formatter<'_>) -> Result {
+        for (i, &row) in self.rows.iter().enumerate() {
+            let pixels = bits(row, self.rows.len(), '●', '·');
+            match i {
+                0 => writeln!(f, "| {:#3} | Matrix    {pixels}", self.address)?,
+                _ => writeln!(f, "| {:#3} |           {pixels}", self.address + i)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt::{Formatter, Result};
+use std::ops::Range;
+
+use super::{bits, Peripheral};
+
+/// Row of 8 or 16 LEDs lit by the bits of the word stored to its address.
+pub struct Leds {
+    address: usize,
+    width: usize,
+    value: u16,
+}
+
+impl Leds {
+    /// Creates a row of the given width, or `None` unless it is 8 or 16.
+    pub fn new(address: usize, width: usize) -> Option<Self> {
+        match width {
+            8 | 16 => Some(Self {
+                address,
+                width,
+                value: 0,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Lit LEDs as bits.
+    pub fn value(&self) -> u16 {
+        self.value
+    }
+
+    fn mask(&self) -> u16 {
+        (u32::MAX >> (32 - self.width)) as u16
+    }
+}
+
+impl Peripheral for Leds {
+    fn name(&self) -> &str {
+        "leds"
+    }
+
+    fn range(&self) -> Range<usize> {
+        self.address..self.address + 1
+    }
+
+    fn peek(&self, _address: usize) -> u16 {
+        self.value
+    }
+
+    fn write(&mut self, _address: usize, value: u16) {
+        self.value = value & self.mask();
+    }
+
+    fn reset(&mut self) {
+        self.value = 0;
+    }
+
+    fn render(&self, f: &mut Formatter<'_>) -> Result {
+        writeln!(
+            f,
+            "| {:#3} | LEDs      {}",
+            self.address,
+            bits(self.value, self.width, '●', '○')
+        )
+    }
+}
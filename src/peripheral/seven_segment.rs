@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+use std::fmt::{Formatter, Result};
+use std::ops::Range;
+
+use super::Peripheral;
+use crate::processor::HISTORY_LIMIT;
+
+/// Multiplexed seven-segment display with up to 8 digits. The word stored
+/// to the first address selects digits by bit, rightmost digit first, and
+/// the word stored to the second address lights segments `a` to `g` and
+/// the decimal point (bits 0 to 7) of every selected digit.
+///
+/// Undo restores every digit as it was before each segment store the
+/// processor can still undo.
+pub struct SevenSegment {
+    address: usize,
+    select: u16,
+    digits: Vec<u8>,
+    history: VecDeque<u64>,
+    history_limit: usize,
+}
+
+impl SevenSegment {
+    /// Creates a display with the given number of digits, or `None` unless
+    /// it has 1 to 8.
+    pub fn new(address: usize, digits: usize) -> Option<Self> {
+        match digits {
+            1..=8 => Some(Self {
+                address,
+                select: 0,
+                digits: vec![0; digits],
+                history: VecDeque::new(),
+                history_limit: HISTORY_LIMIT,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Segments lit on each digit, rightmost digit first.
+    pub fn digits(&self) -> &[u8] {
+        &self.digits
+    }
+
+    fn selected(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.digits.len()).filter(|digit| self.select & (1 << digit) != 0)
+    }
+
+    fn light(&mut self, segments: u8) {
+        for digit in self.selected().collect::<Vec<_>>() {
+            self.digits[digit] = segments;
+        }
+    }
+
+    fn packed(&self) -> u64 {
+        self.digits
+            .iter()
+            .enumerate()
+            .fold(0, |packed, (digit, &segments)| {
+                packed | (segments as u64) << (8 * digit)
+            })
+    }
+}
+
+impl Peripheral for SevenSegment {
+    fn name(&self) -> &str {
+        "7seg"
+    }
+
+    fn range(&self) -> Range<usize> {
+        self.address..self.address + 2
+    }
+
+    fn peek(&self, address: usize) -> u16 {
+        if address == self.address {
+            self.select
+        } else {
+            self.selected()
+                .next()
+                .map_or(0, |digit| self.digits[digit] as u16)
+        }
+    }
+
+    fn write(&mut self, address: usize, value: u16) {
+        if address == self.address {
+            self.select = value;
+        } else {
+            if self.history_limit > 0 {
+                if self.history.len() == self.history_limit {
+                    self.history.pop_front();
+                }
+                self.history.push_back(self.packed());
+            }
+            self.light(value as u8);
+        }
+    }
+
+    fn restore(&mut self, address: usize, value: u16) {
+        if address == self.address {
+            self.select = value;
+            return;
+        }
+        match self.history.pop_back() {
+            Some(packed) => {
+                for (digit, segments) in self.digits.iter_mut().enumerate() {
+                    *segments = (packed >> (8 * digit)) as u8;
+                }
+            }
+            None => self.light(value as u8),
+        }
+    }
+
+    fn set_history_limit(&mut self, limit: usize) {
+        self.history_limit = limit;
+        if self.history.len() > limit {
+            self.history.drain(..self.history.len() - limit);
+        }
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    fn reset(&mut self) {
+        self.select = 0;
+        self.digits.iter_mut().for_each(|digit| *digit = 0);
+        self.history.clear();
+    }
+
+    fn render(&self, f: &mut Formatter<'_>) -> Result {
+        let lit = |segments: u8, bit: u8, glyph: char| {
+            if segments & (1 << bit) != 0 {
+                glyph
+            } else {
+                ' '
+            }
+        };
+        let mut lines = [String::new(), String::new(), String::new()];
+        for &segments in self.digits.iter().rev() {
+            lines[0].extend([' ', lit(segments, 0, '_'), ' ', ' ']);
+            lines[1].extend([
+                lit(segments, 5, '|'),
+                lit(segments, 6, '_'),
+                lit(segments, 1, '|'),
+                ' ',
+            ]);
+            lines[2].extend([
+                lit(segments, 4, '|'),
+                lit(segments, 3, '_'),
+                lit(segments, 2, '|'),
+                lit(segments, 7, '.'),
+            ]);
+        }
+        writeln!(f, "| {:#3} | 7-segment {}", self.address, lines[0])?;
+        writeln!(f, "|     |           {}", lines[1])?;
+        writeln!(f, "|     |           {}", lines[2])
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt::{Formatter, Result};
+use std::ops::Range;
+
+use super::{bits, Peripheral};
+
+/// Bank of 8 or 16 DIP switches read as the bits of a word. Stores to its
+/// address are ignored and the switches keep their position on reset.
+pub struct Switches {
+    address: usize,
+    width: usize,
+    value: u16,
+}
+
+impl Switches {
+    /// Creates a bank of the given width, or `None` unless it is 8 or 16.
+    pub fn new(address: usize, width: usize) -> Option<Self> {
+        match width {
+            8 | 16 => Some(Self {
+                address,
+                width,
+                value: 0,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Flips a switch, returning `false` if there is no such switch.
+    pub fn toggle(&mut self, index: usize) -> bool {
+        if index >= self.width {
+            return false;
+        }
+        self.value ^= 1 << index;
+        true
+    }
+
+    /// Switches which are on, as bits.
+    pub fn value(&self) -> u16 {
+        self.value
+    }
+}
+
+impl Peripheral for Switches {
+    fn name(&self) -> &str {
+        "switches"
+    }
+
+    fn range(&self) -> Range<usize> {
+        self.address..self.address + 1
+    }
+
+    fn peek(&self, _address: usize) -> u16 {
+        self.value
+    }
+
+    fn write(&mut self, _address: usize, _value: u16) {}
+
+    fn render(&self, f: &mut Formatter<'_>) -> Result {
+        writeln!(
+            f,
+            "| {:#3} | Switches  {}",
+            self.address,
+            bits(self.value, self.width, '▲', '▽')
+        )?;
+        let indices = (0..self.width)
+            .rev()
+            .map(|i| format!("{:x}", i))
+            .collect::<Vec<_>>();
+        writeln!(f, "|     |           {}", indices.join(" "))
+    }
+}
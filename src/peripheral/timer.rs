@@ -0,0 +1,137 @@
+use std::fmt::{Formatter, Result};
+use std::ops::Range;
+
+use super::Peripheral;
+
+/// Timer with three consecutive registers: the read-only low 16 bits of
+/// the instruction or cycle counter, a down-counter which starts counting
+/// when stored to, and a status word whose bit 0 is set once the
+/// down-counter reaches zero and can be cleared by storing `0`.
+///
+/// The down-counter and status are derived from the processor counter
+/// passed to [`Peripheral::tick`], so they follow it back on undo.
+pub struct Timer {
+    address: usize,
+    cycles: bool,
+    clock: usize,
+    /// Clock at which the down-counter was last stored to.
+    start: usize,
+    /// Value stored to the down-counter.
+    initial: u16,
+    /// Status as of `status_since`, excluding later expiry.
+    status: u16,
+    status_since: usize,
+}
+
+impl Timer {
+    /// Creates a timer counting executed instructions or clock cycles.
+    pub fn new(address: usize, cycles: bool) -> Self {
+        Self {
+            address,
+            cycles,
+            clock: 0,
+            start: 0,
+            initial: 0,
+            status: 0,
+            status_since: 0,
+        }
+    }
+
+    /// Current value of the down-counter.
+    pub fn countdown(&self) -> u16 {
+        let elapsed = self.clock.saturating_sub(self.start);
+        self.initial
+            .saturating_sub(elapsed.min(u16::MAX as usize) as u16)
+    }
+
+    /// Current status word, including expiry of the down-counter.
+    pub fn status(&self) -> u16 {
+        let expiry = self.start + self.initial as usize;
+        if self.initial > 0 && self.status_since < expiry && expiry <= self.clock {
+            self.status | 1
+        } else {
+            self.status
+        }
+    }
+
+    /// Whether the down-counter has run out since the status was cleared.
+    pub fn expired(&self) -> bool {
+        self.status() & 1 != 0
+    }
+}
+
+impl Peripheral for Timer {
+    fn name(&self) -> &str {
+        "timer"
+    }
+
+    fn range(&self) -> Range<usize> {
+        self.address..self.address + 3
+    }
+
+    fn peek(&self, address: usize) -> u16 {
+        match address - self.address {
+            0 => self.clock as u16,
+            1 => self.countdown(),
+            _ => self.status(),
+        }
+    }
+
+    fn write(&mut self, address: usize, value: u16) {
+        match address - self.address {
+            0 => {}
+            1 => {
+                self.status = self.status();
+                self.status_since = self.clock;
+                self.start = self.clock;
+                self.initial = value;
+            }
+            _ if value == 0 => {
+                self.status = 0;
+                self.status_since = self.clock;
+            }
+            _ => {}
+        }
+    }
+
+    /// Undoes a store, given the value the register had before it, once the
+    /// clock is back where it was when the store executed. A countdown
+    /// which had expired before is already part of the status.
+    fn restore(&mut self, address: usize, value: u16) {
+        match address - self.address {
+            0 => {}
+            1 => {
+                self.start = self.clock;
+                self.initial = value;
+            }
+            _ => {
+                self.status = value;
+                self.status_since = self.clock;
+            }
+        }
+    }
+
+    fn tick(&mut self, instructions: usize, cycles: usize) {
+        self.clock = if self.cycles { cycles } else { instructions };
+    }
+
+    fn reset(&mut self) {
+        self.clock = 0;
+        self.start = 0;
+        self.initial = 0;
+        self.status = 0;
+        self.status_since = 0;
+    }
+
+    fn render(&self, f: &mut Formatter<'_>) -> Result {
+        writeln!(
+            f,
+            "| {:#3} | Timer     {} {}, countdown {}{}",
+            self.address,
+            if self.cycles { "cycle" } else { "instruction" },
+            self.clock as u16,
+            self.countdown(),
+            if self.expired() { " (expired)" } else { "" }
+        )
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt::{Formatter, Result};
+use std::ops::Range;
+
+use super::Peripheral;
+
+const PANE_LINES: usize = 8;
+
+/// Character console with three consecutive registers: storing to the
+/// first one prints a character, loading from the second one takes the
+/// next queued input character (`0` if there is none) and the third one
+/// holds the number of queued input characters.
+///
+/// Reset clears the output and queues the whole input again.
+pub struct Uart {
+    address: usize,
+    output: String,
+    input: Vec<u16>,
+    position: usize,
+}
+
+impl Uart {
+    /// Creates a console with an empty input queue.
+    pub fn new(address: usize) -> Self {
+        Self {
+            address,
+            output: String::new(),
+            input: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Characters printed so far.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Appends text to the input queue.
+    pub fn queue(&mut self, text: &str) {
+        self.input.extend(text.bytes().map(u16::from));
+    }
+
+    /// Number of input characters not taken yet.
+    pub fn queued(&self) -> usize {
+        self.input.len() - self.position
+    }
+}
+
+impl Peripheral for Uart {
+    fn name(&self) -> &str {
+        "uart"
+    }
+
+    fn range(&self) -> Range<usize> {
+        self.address..self.address + 3
+    }
+
+    fn read(&mut self, address: usize) -> u16 {
+        let value = self.peek(address);
+        if address == self.address + 1 && self.queued() > 0 {
+            self.position += 1;
+        }
+        value
+    }
+
+    fn peek(&self, address: usize) -> u16 {
+        match address - self.address {
+            0 => self.output.chars().last().map_or(0, |c| c as u16),
+            1 => self.input.get(self.position).copied().unwrap_or(0),
+            _ => self.queued().min(u16::MAX as usize) as u16,
+        }
+    }
+
+    fn write(&mut self, address: usize, value: u16) {
+        if address == self.address {
+            self.output.push(char::from(value as u8));
+        }
+    }
+
+    fn restore(&mut self, address: usize, _value: u16) {
+        if address == self.address {
+            self.output.pop();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.output.clear();
+        self.position = 0;
+    }
+
+    fn render(&self, f: &mut Formatter<'_>) -> Result {
+        writeln!(
+            f,
+            "| {:#3} | Console   ({} character(s) queued)",
+            self.address,
+            self.queued()
+        )?;
+        let lines = self.output.split('\n').collect::<Vec<_>>();
+        for line in &lines[lines.len().saturating_sub(PANE_LINES)..] {
+            writeln!(f, "|     | > {}", line.escape_debug())?;
+        }
+        Ok(())
+    }
+}
@@ -1,45 +1,120 @@
+//! The LPRS1 CPU and its debugger: breakpoints, watches, undo history,
+//! profiling and coverage.
+
+mod breakpoint;
+mod coverage;
+mod cycle_model;
 mod error;
-pub mod flag_registers;
-pub mod implementation;
+mod flag_registers;
+mod implementation;
+mod journal;
+mod loop_detector;
+mod machine_config;
+mod profile;
+mod run_outcome;
+mod stop_reason;
+mod watchpoint;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 use crate::instructions::Instruction;
+use crate::peripheral::Peripheral;
+use crate::trace::Tracer;
+pub use breakpoint::Breakpoint;
+pub use coverage::{Coverage, CoverageEntry};
+pub use cycle_model::{CycleModel, InstructionClass};
 pub use error::EmulationError;
+use journal::Journal;
+pub use journal::HISTORY_LIMIT;
+use loop_detector::LoopDetector;
+pub use machine_config::MachineConfig;
+pub use profile::{Profile, ProfileEntry, ProfileGroup};
+pub use run_outcome::RunOutcome;
+pub use stop_reason::StopReason;
+pub use watchpoint::{RegisterWatch, RegisterWatchKind, WatchKind, Watchpoint};
 
+/// Words of program memory in the default machine.
 pub const ROM_SIZE: usize = 256;
+/// Words of data memory in the default machine.
 pub const RAM_SIZE: usize = 256;
+/// Registers in the default machine.
 pub const REG_COUNT: usize = 8;
 
-#[derive(Debug, Default)]
-struct FlagRegisters {
-    zero: bool,
-    sign: bool,
-    carry: bool,
+/// Status flags set by the ALU and memory instructions.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FlagRegisters {
+    /// Result was zero.
+    pub zero: bool,
+    /// Result was negative as a signed word.
+    pub sign: bool,
+    /// Result carried or borrowed, or a shift moved out a one.
+    pub carry: bool,
+}
+
+/// One of the [`FlagRegisters`], as named in expressions and watches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Flag {
+    /// `zero`
+    Zero,
+    /// `sign`
+    Sign,
+    /// `carry`
+    Carry,
 }
 
+/// Interpretation of decimal values in the state view.
 #[derive(Debug)]
 pub enum DisplaySigned {
+    /// Values are shown from 0 to 65535.
     Unsigned,
+    /// Values are shown as two's complement words.
     Signed,
 }
 
+/// Number format used for registers and memory in the state view.
 #[derive(Debug)]
 pub enum DisplayRadix {
+    /// Base 10.
     Decimal(DisplaySigned),
+    /// Base 16.
     Hexadecimal,
+    /// Base 2.
     Binary,
 }
 
+/// LPRS1 CPU with its program and data memories and debugger state.
 pub struct Processor {
-    rom: [Instruction; ROM_SIZE],
-    ram: [u16; RAM_SIZE],
-    ram_initial: [u16; RAM_SIZE],
-    registers: [u16; REG_COUNT],
+    config: MachineConfig,
+    rom: Vec<Instruction>,
+    ram: Vec<u16>,
+    ram_initial: Vec<u16>,
+    ram_hash: u64,
+    registers: Vec<u16>,
     flags: FlagRegisters,
     program_counter: usize,
     runtime_counter: usize,
-    breakpoints: [bool; ROM_SIZE],
+    cycle_counter: usize,
+    cycle_model: CycleModel,
+    executions: Vec<usize>,
+    jumps_taken: Vec<usize>,
+    breakpoints: Vec<Option<Breakpoint>>,
+    watchpoints: Vec<Watchpoint>,
+    register_watches: Vec<RegisterWatch>,
+    flag_watches: Vec<Flag>,
+    stop: Option<StopReason>,
+    logs: VecDeque<String>,
+    log_output: Option<Box<dyn Write>>,
+    detect_loops: bool,
+    interrupt: Option<Arc<AtomicBool>>,
+    step_limit: Option<usize>,
+    loop_detector: LoopDetector,
     radix: DisplayRadix,
     labels: HashMap<usize, Vec<String>>,
+    journal: Journal,
+    tracer: Option<Tracer>,
+    trace_error: Option<std::io::Error>,
+    peripherals: Vec<Box<dyn Peripheral>>,
 }
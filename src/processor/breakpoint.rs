@@ -0,0 +1,56 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::parser::{Expression, Location, LogFormat};
+
+/// Breakpoint or logpoint on a program memory address.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Breakpoint {
+    /// Location as it was given, kept to move the breakpoint on reload.
+    pub location: Location,
+    /// Expression which has to be non-zero for the breakpoint to trigger.
+    pub condition: Option<Expression>,
+    /// Times the breakpoint triggered.
+    pub hits: usize,
+    /// Remaining triggers to pass without stopping.
+    pub ignore: usize,
+    /// Message recorded by a logpoint.
+    pub log: Option<LogFormat>,
+}
+
+impl Breakpoint {
+    /// Creates a breakpoint, which stops execution.
+    pub fn new(location: Location, condition: Option<Expression>) -> Self {
+        Self {
+            location,
+            condition,
+            hits: 0,
+            ignore: 0,
+            log: None,
+        }
+    }
+
+    /// Creates a logpoint, which records a message instead of stopping.
+    pub fn logpoint(location: Location, condition: Option<Expression>, log: LogFormat) -> Self {
+        Self {
+            log: Some(log),
+            ..Self::new(location, condition)
+        }
+    }
+}
+
+impl Display for Breakpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.location)?;
+        if let Some(log) = &self.log {
+            write!(f, " log \"{log}\"")?;
+        }
+        if let Some(condition) = &self.condition {
+            write!(f, " if {condition}")?;
+        }
+        write!(f, ", hit {} time(s)", self.hits)?;
+        if self.ignore > 0 {
+            write!(f, ", ignore next {}", self.ignore)?;
+        }
+        Ok(())
+    }
+}
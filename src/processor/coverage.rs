@@ -0,0 +1,122 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::instructions::Instruction;
+
+/// Execution statistics of a single program memory address.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoverageEntry {
+    /// Program memory address.
+    pub address: usize,
+    /// Labels defined at the address.
+    pub labels: Vec<String>,
+    /// Instruction at the address.
+    pub instruction: Instruction,
+    /// Label of the jump target, if the instruction jumps to one.
+    pub target: Option<String>,
+    /// Times the instruction was executed.
+    pub executions: usize,
+    /// Times a conditional jump was taken and fell through.
+    pub branch: Option<(usize, usize)>,
+}
+
+/// Executed instructions and conditional jump directions of a program.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coverage {
+    /// Entries of every address up to the last instruction.
+    pub entries: Vec<CoverageEntry>,
+}
+
+impl Coverage {
+    /// Returns the number of instructions executed at least once.
+    pub fn executed(&self) -> usize {
+        self.entries.iter().filter(|e| e.executions > 0).count()
+    }
+
+    /// Returns the number of covered branch directions and of all of them.
+    pub fn branch_directions(&self) -> (usize, usize) {
+        self.entries.iter().filter_map(|e| e.branch).fold(
+            (0, 0),
+            |(covered, total), (taken, fallen)| {
+                (
+                    covered + (taken > 0) as usize + (fallen > 0) as usize,
+                    total + 2,
+                )
+            },
+        )
+    }
+
+    /// Machine-readable summary as a JSON object.
+    pub fn to_json(&self) -> String {
+        let (covered, total) = self.branch_directions();
+        let join = |items: Vec<String>| items.join(",");
+        format!(
+            "{{\"instructions\":{},\"executed\":{},\"branch_directions\":{},\
+             \"branch_directions_covered\":{},\"unexecuted\":[{}],\
+             \"executions\":[{}],\"branches\":[{}]}}",
+            self.entries.len(),
+            self.executed(),
+            total,
+            covered,
+            join(
+                self.entries
+                    .iter()
+                    .filter(|e| e.executions == 0)
+                    .map(|e| e.address.to_string())
+                    .collect()
+            ),
+            join(
+                self.entries
+                    .iter()
+                    .map(|e| e.executions.to_string())
+                    .collect()
+            ),
+            join(
+                self.entries
+                    .iter()
+                    .filter_map(|e| {
+                        let (taken, fallen) = e.branch?;
+                        Some(format!(
+                            "{{\"address\":{},\"taken\":{taken},\"not_taken\":{fallen}}}",
+                            e.address
+                        ))
+                    })
+                    .collect()
+            ),
+        )
+    }
+}
+
+impl Display for Coverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let (covered, total) = self.branch_directions();
+        writeln!(
+            f,
+            "Coverage: {}/{} instruction(s), {covered}/{total} branch direction(s)",
+            self.executed(),
+            self.entries.len()
+        )?;
+        let indent = if self.entries.iter().all(|e| e.labels.is_empty()) {
+            ""
+        } else {
+            "    "
+        };
+        for entry in &self.entries {
+            for label in &entry.labels {
+                writeln!(f, "|     |        | {label}:")?;
+            }
+            match entry.executions {
+                0 => write!(f, "| {:#3} |  ##### |", entry.address)?,
+                n => write!(f, "| {:#3} | {n:>6} |", entry.address)?,
+            }
+            write!(f, "{indent} {}", entry.instruction)?;
+            if let Some(target) = &entry.target {
+                write!(f, " ({target})")?;
+            }
+            if let Some((taken, fallen)) = entry.branch {
+                write!(f, " [taken {taken}, not taken {fallen}]")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
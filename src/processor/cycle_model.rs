@@ -0,0 +1,136 @@
+use std::fmt::{Display, Formatter, Result};
+use std::str::FromStr;
+
+use crate::instructions::Instruction;
+
+/// Group of instructions sharing a cycle count in a [`CycleModel`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InstructionClass {
+    /// [`Instruction::Alu`]
+    Alu,
+    /// [`Instruction::Memory`]
+    Memory,
+    /// [`Instruction::ControlFlow`]
+    ControlFlow,
+    /// [`Instruction::Debug`]
+    Debug,
+    /// [`Instruction::NoOperation`]
+    NoOperation,
+}
+
+/// Clock cycles spent by each instruction class. The defaults assume a
+/// multi-cycle CPU with fetch, decode and execute states and an extra memory
+/// state for loads and stores. Debug instructions only exist in the emulator
+/// and take no cycles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CycleModel {
+    /// Cycles of an ALU instruction.
+    pub alu: u32,
+    /// Cycles of a load or store.
+    pub memory: u32,
+    /// Cycles of a jump, taken or not.
+    pub control_flow: u32,
+    /// Cycles of a debug instruction.
+    pub debug: u32,
+    /// Cycles of a `nop`.
+    pub no_operation: u32,
+}
+
+impl Default for CycleModel {
+    fn default() -> Self {
+        Self {
+            alu: 3,
+            memory: 4,
+            control_flow: 3,
+            debug: 0,
+            no_operation: 3,
+        }
+    }
+}
+
+impl CycleModel {
+    /// Cycles spent executing an instruction.
+    pub fn cycles(&self, op: Instruction) -> u32 {
+        self.get(InstructionClass::from(op))
+    }
+
+    /// Cycles of an instruction class.
+    pub fn get(&self, class: InstructionClass) -> u32 {
+        match class {
+            InstructionClass::Alu => self.alu,
+            InstructionClass::Memory => self.memory,
+            InstructionClass::ControlFlow => self.control_flow,
+            InstructionClass::Debug => self.debug,
+            InstructionClass::NoOperation => self.no_operation,
+        }
+    }
+
+    /// Changes the cycles of an instruction class.
+    pub fn set(&mut self, class: InstructionClass, cycles: u32) {
+        match class {
+            InstructionClass::Alu => self.alu = cycles,
+            InstructionClass::Memory => self.memory = cycles,
+            InstructionClass::ControlFlow => self.control_flow = cycles,
+            InstructionClass::Debug => self.debug = cycles,
+            InstructionClass::NoOperation => self.no_operation = cycles,
+        }
+    }
+}
+
+impl From<Instruction> for InstructionClass {
+    fn from(op: Instruction) -> Self {
+        match op {
+            Instruction::Alu(_) => Self::Alu,
+            Instruction::Memory(_) => Self::Memory,
+            Instruction::ControlFlow(_) => Self::ControlFlow,
+            Instruction::Debug(_) => Self::Debug,
+            Instruction::NoOperation => Self::NoOperation,
+        }
+    }
+}
+
+impl FromStr for InstructionClass {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "alu" => Ok(Self::Alu),
+            "memory" | "mem" => Ok(Self::Memory),
+            "jump" | "control-flow" => Ok(Self::ControlFlow),
+            "debug" => Ok(Self::Debug),
+            "nop" => Ok(Self::NoOperation),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for InstructionClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Alu => write!(f, "alu"),
+            Self::Memory => write!(f, "memory"),
+            Self::ControlFlow => write!(f, "jump"),
+            Self::Debug => write!(f, "debug"),
+            Self::NoOperation => write!(f, "nop"),
+        }
+    }
+}
+
+impl Display for CycleModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let classes = [
+            InstructionClass::Alu,
+            InstructionClass::Memory,
+            InstructionClass::ControlFlow,
+            InstructionClass::Debug,
+            InstructionClass::NoOperation,
+        ];
+        for (i, class) in classes.into_iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{class}: {}", self.get(class))?;
+        }
+        Ok(())
+    }
+}
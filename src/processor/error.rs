@@ -1,10 +1,14 @@
+/// Reason an instruction could not be decoded or executed.
 #[derive(Clone, Copy, Debug)]
 pub enum EmulationError {
+    /// Binary instruction field has the wrong number of bits.
     InvalidLength,
+    /// Binary instruction field is not made of `0` and `1`.
     BinaryParsing,
+    /// Binary opcode does not name an instruction.
     InvalidInstruction,
+    /// Instruction refers to a register or address the machine lacks.
     OutOfRange,
-    StackOverflow,
 }
 
 impl std::fmt::Display for EmulationError {
@@ -1,10 +1,43 @@
-use super::FlagRegisters;
+use super::{Flag, FlagRegisters};
 
 impl FlagRegisters {
+    /// Clears all flags.
     pub fn unset(&mut self) -> &mut Self {
         self.zero = false;
         self.sign = false;
         self.carry = false;
         self
     }
+
+    /// Value of a single flag.
+    pub fn get(&self, flag: Flag) -> bool {
+        match flag {
+            Flag::Zero => self.zero,
+            Flag::Sign => self.sign,
+            Flag::Carry => self.carry,
+        }
+    }
+}
+
+impl std::str::FromStr for Flag {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zero" => Ok(Self::Zero),
+            "sign" => Ok(Self::Sign),
+            "carry" => Ok(Self::Carry),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for Flag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Zero => write!(f, "zero"),
+            Self::Sign => write!(f, "sign"),
+            Self::Carry => write!(f, "carry"),
+        }
+    }
 }
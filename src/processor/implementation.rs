@@ -1,38 +1,58 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
+use super::journal::{Journal, JournalEntry};
 use super::EmulationError;
-use super::{DisplayRadix, DisplaySigned, FlagRegisters, Processor, RAM_SIZE, REG_COUNT, ROM_SIZE};
+use super::{
+    CycleModel, DisplayRadix, DisplaySigned, FlagRegisters, LoopDetector, MachineConfig, Processor,
+    RunOutcome, StopReason, HISTORY_LIMIT,
+};
 use crate::instructions::{Instruction, RegisterBoundCheck};
+use crate::parser::{Location, ParsingError};
+use crate::trace::TraceRecord;
+use control_flow::jump_taken;
 
 mod alu;
+mod breakpoint;
 mod control_flow;
+mod coverage;
+mod cycles;
 mod debug;
 mod display;
+mod expression;
+mod loops;
 mod memory;
+mod peripheral;
+mod profile;
+mod run_control;
+mod trace;
+mod undo;
+mod watch;
+
+/// Steps between checks of the interrupt flag during a run.
+const INTERRUPT_POLL_INTERVAL: u16 = 1 << 12;
 
-#[macro_export]
 macro_rules! in_range {
-    ($thresh:ident; $($v:expr),*) => {
-        if $($v as usize >= $crate::processor::$thresh as usize)||* {
+    ($thresh:expr; $($v:expr),*) => {
+        if $($v as usize >= $thresh)||* {
             return Err(EmulationError::OutOfRange);
         }
     };
 }
 
-#[macro_export]
 macro_rules! reg {
     ($self:ident; $i:ident) => {
         $self.registers[$i as usize]
     };
 }
 
-#[macro_export]
 macro_rules! mem {
     ($self:ident; $i:expr) => {
         $self.ram[$i as usize]
     };
 }
 
+use {in_range, mem, reg};
+
 impl Default for Processor {
     fn default() -> Self {
         Self::new()
@@ -40,30 +60,58 @@ impl Default for Processor {
 }
 
 impl Processor {
-    #[allow(dead_code)]
-    fn new() -> Self {
+    /// Creates a processor of the reference geometry with empty memories.
+    pub fn new() -> Self {
+        Self::with_config(MachineConfig::default())
+    }
+
+    /// Creates a processor with empty memories of the given sizes.
+    pub fn with_config(config: MachineConfig) -> Self {
         Processor {
-            rom: [Instruction::default(); ROM_SIZE],
-            ram: [0; RAM_SIZE],
-            ram_initial: [0; RAM_SIZE],
-            registers: [0; REG_COUNT],
+            config,
+            rom: vec![Instruction::default(); config.rom_size()],
+            ram: vec![0; config.ram_size()],
+            ram_initial: vec![0; config.ram_size()],
+            ram_hash: 0,
+            registers: vec![0; config.register_count()],
             flags: FlagRegisters::default(),
             program_counter: 0,
             runtime_counter: 0,
-            breakpoints: [false; ROM_SIZE],
+            cycle_counter: 0,
+            cycle_model: CycleModel::default(),
+            executions: vec![0; config.rom_size()],
+            jumps_taken: vec![0; config.rom_size()],
+            breakpoints: vec![None; config.rom_size()],
+            watchpoints: Vec::new(),
+            register_watches: Vec::new(),
+            flag_watches: Vec::new(),
+            stop: None,
+            logs: VecDeque::new(),
+            log_output: None,
+            detect_loops: true,
+            interrupt: None,
+            step_limit: None,
+            loop_detector: LoopDetector::default(),
             radix: DisplayRadix::Decimal(DisplaySigned::Signed),
             labels: HashMap::new(),
+            journal: Journal::new(HISTORY_LIMIT),
+            tracer: None,
+            trace_error: None,
+            peripherals: Vec::new(),
         }
     }
 
-    #[allow(dead_code)]
+    /// Replaces the program memory, clearing its labels.
     pub fn load_rom(&mut self, instructions: &[Instruction]) -> &mut Self {
         self.clear_rom();
         self.rom[0..instructions.len()].copy_from_slice(instructions);
+        self.executions.fill(0);
+        self.jumps_taken.fill(0);
+        self.clear_history();
         self
     }
 
-    #[allow(dead_code)]
+    /// Fills the program memory with `nop` instructions.
     pub fn clear_rom(&mut self) {
         self.rom
             .iter_mut()
@@ -71,23 +119,28 @@ impl Processor {
         self.labels.clear();
     }
 
-    #[allow(dead_code)]
+    /// Loads the program memory from binary encoded instructions.
     pub fn load_rom_str(&mut self, instructions: &[&str]) -> Result<&mut Self, EmulationError> {
+        self.executions.fill(0);
+        self.jumps_taken.fill(0);
+        self.clear_history();
         for (i, op) in instructions.iter().enumerate() {
             self.rom[i] = op.parse()?;
         }
         Ok(self)
     }
 
-    #[allow(dead_code)]
+    /// Replaces the data memory and its initial state used by [`Self::reset`].
     pub fn load_ram(&mut self, data: &[u16]) -> &mut Self {
         self.clear_ram();
         self.ram[0..data.len()].copy_from_slice(data);
         self.ram_initial.copy_from_slice(&self.ram);
+        self.rehash_ram();
+        self.clear_history();
         self
     }
 
-    #[allow(dead_code)]
+    /// Sets the labels shown next to program memory addresses.
     pub fn load_labels(&mut self, labels: HashMap<usize, Vec<String>>) -> &mut Self {
         self.labels = labels;
         self
@@ -95,47 +148,127 @@ impl Processor {
 
     fn clear_ram(&mut self) {
         self.ram.iter_mut().for_each(|cell| *cell = 0);
+        self.rehash_ram();
     }
 
-    #[allow(dead_code)]
-    pub fn clear_breakpoints(&mut self) -> &mut Self {
-        self.breakpoints = [false; ROM_SIZE];
-        self
-    }
-
-    #[allow(dead_code)]
+    /// Restores the initial data memory and clears registers, flags and counters.
     pub fn reset(&mut self) {
         self.ram.copy_from_slice(&self.ram_initial);
-        self.registers = [0; REG_COUNT];
+        self.rehash_ram();
+        self.registers.fill(0);
         self.flags = FlagRegisters::default();
         self.program_counter = 0;
         self.runtime_counter = 0;
+        self.cycle_counter = 0;
+        self.executions.fill(0);
+        self.jumps_taken.fill(0);
+        self.clear_history();
+        self.logs.clear();
+        self.breakpoints
+            .iter_mut()
+            .flatten()
+            .for_each(|breakpoint| breakpoint.hits = 0);
+        self.peripherals
+            .iter_mut()
+            .for_each(|peripheral| peripheral.reset());
     }
 
-    #[allow(dead_code)]
+    /// Sets the number format used by the state view.
     pub fn set_radix(&mut self, radix: DisplayRadix) {
         self.radix = radix;
     }
 
-    #[allow(dead_code)]
+    /// Executes a single instruction, returning `false` once execution
+    /// leaves program memory by halting or running past its last cell. A
+    /// trace which cannot be written stops, leaving the error for
+    /// [`Self::take_trace_error`].
     pub fn tick(&mut self) -> Result<bool, EmulationError> {
-        if self.program_counter >= ROM_SIZE {
-            return Ok(false);
+        if self.tracer.is_none() {
+            return self.step::<false, true>().map(|(running, _)| running);
         }
+        let (running, record) = self.step::<true, true>()?;
+        if let (Some(tracer), Some(record)) = (&mut self.tracer, record) {
+            if let Err(e) = tracer.record(&record) {
+                self.tracer = None;
+                self.trace_error = Some(e);
+            }
+        }
+        Ok(running)
+    }
+
+    /// Executes a single instruction like [`Self::tick`], also returning the
+    /// record of its state changes.
+    pub fn tick_traced(&mut self) -> Result<(bool, Option<TraceRecord>), EmulationError> {
+        self.step::<true, true>()
+    }
+
+    /// Executes the instruction at the program counter. Without `HOOKS` it
+    /// skips logpoints, watches, peripherals and the undo history, which
+    /// [`Self::hooked`] tells apart.
+    fn step<const TRACE: bool, const HOOKS: bool>(
+        &mut self,
+    ) -> Result<(bool, Option<TraceRecord>), EmulationError> {
+        self.stop = None;
         let current_counter = self.program_counter;
-        self.tick_op(self.rom[current_counter])?;
-        if self.program_counter == current_counter {
-            if self.program_counter < ROM_SIZE - 1 {
+        let Some(&op) = self.rom.get(current_counter) else {
+            return Ok((false, None));
+        };
+        if HOOKS && self.breakpoints[current_counter].is_some() {
+            self.logpoint_hit(current_counter);
+        }
+        let cycles = self.cycle_model.cycles(op);
+        let watching = HOOKS
+            && !(self.watchpoints.is_empty()
+                && self.register_watches.is_empty()
+                && self.flag_watches.is_empty());
+        let change =
+            (TRACE || watching || HOOKS && self.journal.enabled()).then(|| self.journal_change(op));
+        let flags = self.flags;
+        let hit = if watching {
+            self.watchpoint_hit(op)
+        } else {
+            None
+        };
+        let access = if TRACE { self.memory_access(op) } else { None };
+        let taken = matches!(op, Instruction::ControlFlow(jump) if jump_taken(jump, self.flags));
+        self.tick_op(op)?;
+        self.cycle_counter += cycles as usize;
+        self.executions[current_counter] += 1;
+        if taken {
+            self.jumps_taken[current_counter] += 1;
+        }
+        if HOOKS && !self.peripherals.is_empty() {
+            self.tick_peripherals();
+        }
+        let mut record = None;
+        if let Some(change) = change {
+            if watching {
+                self.stop = hit.or_else(|| self.register_watch_hit(change, flags));
+            }
+            if TRACE {
+                record = Some(self.trace_record(current_counter, op, change, access));
+            }
+            self.journal.push(JournalEntry {
+                program_counter: current_counter as u16,
+                flags,
+                change,
+                cycles,
+            });
+        }
+        let running = if self.program_counter == current_counter && !taken {
+            if self.program_counter < self.rom.len() - 1 {
                 self.program_counter += 1;
-                Ok(true)
+                true
             } else {
-                Ok(false)
+                false
             }
         } else {
-            Ok(current_counter < ROM_SIZE - 1)
-        }
+            self.program_counter < self.rom.len()
+        };
+        Ok((running, record))
     }
 
+    #[inline(always)]
     fn tick_op(&mut self, op: Instruction) -> Result<(), EmulationError> {
         match op {
             Instruction::Alu(op) => self.execute_alu(op)?,
@@ -148,59 +281,81 @@ impl Processor {
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub fn run(
-        &mut self,
-        breakpoints: bool,
-        count: Option<isize>,
-    ) -> Result<usize, EmulationError> {
-        let instruction_count = self.runtime_counter;
-        let end = if let Instruction::NoOperation = self.rom[self.last_instruction_address()] {
-            if self.last_instruction_address() == 0 {
-                return Ok(0);
-            }
-            self.last_instruction_address() - 1
-        } else {
-            self.last_instruction_address()
+    /// Executes instructions until the end of the program, stopping early
+    /// on breakpoints and watchpoints if enabled, or after `count`
+    /// instructions or the step limit, whichever is smaller. Returns how the
+    /// run ended.
+    pub fn run(&mut self, breakpoints: bool, count: Option<usize>) -> RunOutcome {
+        let Some(end) = self.end_address() else {
+            return self.end_outcome();
         };
-        let runtime_end = match count {
-            Some(v) => {
-                if v >= 0 {
-                    self.runtime_counter + v as usize
-                } else {
-                    let count = if self.runtime_counter > 0 {
-                        self.runtime_counter as isize + v
-                    } else {
-                        0
-                    };
-                    self.reset();
-                    count as usize
+        let limit = match (count, self.step_limit) {
+            (Some(count), Some(limit)) => Some(count.min(limit)),
+            (count, limit) => count.or(limit),
+        };
+        let runtime_end = limit.map_or(usize::MAX, |limit| {
+            self.runtime_counter.saturating_add(limit)
+        });
+        let hooked = self.hooked();
+        self.loop_detector.clear();
+        self.clear_interrupt();
+        let mut poll = 0u16;
+        while self.program_counter < end || self.program_counter == end && !self.parked() {
+            if self.runtime_counter >= runtime_end {
+                if let Some(limit) = limit {
+                    let reason = StopReason::StepLimit(limit);
+                    self.stop = Some(reason);
+                    return RunOutcome::Stopped(reason);
                 }
             }
-            None => usize::MAX,
-        };
-        while self.program_counter <= end && self.runtime_counter < runtime_end {
-            if !self.tick()? {
-                return Err(EmulationError::StackOverflow);
+            poll = poll.wrapping_add(1);
+            if poll % INTERRUPT_POLL_INTERVAL == 0 && self.interrupted() {
+                self.stop = Some(StopReason::Interrupted);
+                return RunOutcome::Stopped(StopReason::Interrupted);
+            }
+            let line = self.program_counter;
+            let running = match hooked {
+                true => self.tick(),
+                false => self.step::<false, false>().map(|(running, _)| running),
             };
-            if breakpoints && self.breakpoints[self.program_counter] {
-                break;
+            match running {
+                Ok(true) => {}
+                Ok(false) => return self.end_outcome(),
+                Err(e) => return RunOutcome::Error(e),
+            }
+            if self.detect_loops && self.program_counter <= line {
+                if let Some(reason) = self.detect_loop(line) {
+                    self.stop = Some(reason);
+                    return RunOutcome::Stopped(reason);
+                }
+            }
+            if let (true, Some(reason)) = (breakpoints, self.stop) {
+                return RunOutcome::Stopped(reason);
+            }
+            if breakpoints {
+                if let Some(reason) = self.breakpoint_hit(self.program_counter) {
+                    self.stop = Some(reason);
+                    return RunOutcome::Stopped(reason);
+                }
             }
         }
-        Ok((self.runtime_counter as isize - instruction_count as isize) as usize)
+        self.end_outcome()
     }
 
-    #[allow(dead_code)]
-    pub fn toggle_breakpoint(&mut self, line: usize) -> bool {
-        if line > self.rom.len() {
-            false
-        } else {
-            self.breakpoints[line] = !self.breakpoints[line];
-            self.breakpoints[line]
-        }
+    /// Whether steps have to look for logpoints, watches or peripherals, or
+    /// record undo history or a trace. Running the program cannot change
+    /// any of these.
+    fn hooked(&self) -> bool {
+        self.tracer.is_some()
+            || self.journal.enabled()
+            || !self.peripherals.is_empty()
+            || !self.watchpoints.is_empty()
+            || !self.register_watches.is_empty()
+            || !self.flag_watches.is_empty()
+            || self.breakpoints.iter().flatten().any(|b| b.log.is_some())
     }
 
-    #[allow(dead_code)]
+    /// Moves the program counter to a program memory address.
     pub fn program_counter_jump(&mut self, line: usize) -> bool {
         if line > self.rom.len() {
             false
@@ -210,6 +365,84 @@ impl Processor {
         }
     }
 
+    /// Memory sizes and register count of the emulated CPU.
+    pub fn config(&self) -> MachineConfig {
+        self.config
+    }
+
+    /// Program memory, including the `nop` instructions after the program.
+    pub fn rom(&self) -> &[Instruction] {
+        &self.rom
+    }
+
+    /// Current contents of the data memory.
+    pub fn ram(&self) -> &[u16] {
+        &self.ram
+    }
+
+    /// Current values of the general purpose registers.
+    pub fn registers(&self) -> &[u16] {
+        &self.registers
+    }
+
+    /// Flags set by the last ALU instruction.
+    pub fn flags(&self) -> FlagRegisters {
+        self.flags
+    }
+
+    /// Address of the next instruction to execute.
+    pub fn program_counter(&self) -> usize {
+        self.program_counter
+    }
+
+    /// Number of instructions executed since the last reset.
+    pub fn runtime_counter(&self) -> usize {
+        self.runtime_counter
+    }
+
+    /// Labels of the loaded program by program memory address.
+    pub fn labels(&self) -> &HashMap<usize, Vec<String>> {
+        &self.labels
+    }
+
+    /// Program memory address of a label, if it is defined.
+    pub fn label_address(&self, label: &str) -> Option<usize> {
+        self.labels
+            .iter()
+            .find(|(_, names)| names.iter().any(|name| name == label))
+            .map(|(&addr, _)| addr)
+    }
+
+    /// Resolves a location to a program memory address using the labels.
+    pub fn resolve(&self, location: &Location) -> Result<usize, ParsingError> {
+        let address = match location {
+            Location::Address(address) => Some(*address),
+            Location::Label(label, offset) => {
+                let address = self
+                    .label_address(label)
+                    .ok_or_else(|| ParsingError::UndefinedLabel(label.clone()))?;
+                address.checked_add_signed(*offset)
+            }
+        };
+        match address {
+            Some(address) if address < self.rom.len() => Ok(address),
+            _ => Err(ParsingError::InvalidAddress(location.to_string())),
+        }
+    }
+
+    /// Returns `true` once the program counter is past the last instruction,
+    /// or stays on it because it is a jump to itself.
+    pub fn finished(&self) -> bool {
+        match self.end_address() {
+            Some(end) => self.program_counter > end || self.program_counter == end && self.parked(),
+            None => true,
+        }
+    }
+
+    fn end_address(&self) -> Option<usize> {
+        self.last_instruction_address().checked_sub(1)
+    }
+
     fn last_instruction_address(&self) -> usize {
         self.rom.len()
             - self
@@ -226,9 +459,10 @@ impl Processor {
                 .count()
     }
 
+    /// Validates register and address operands of every instruction.
     pub fn check(&self) -> Result<(), Instruction> {
-        for ins in self.rom {
-            if !ins.reg_bound_check() {
+        for &ins in &self.rom {
+            if !ins.reg_bound_check(&self.config) {
                 return Err(ins);
             }
         }
@@ -0,0 +1,224 @@
+use std::io::Write;
+
+use super::Processor;
+
+use crate::parser::{Expression, Location, LogFormat, LogSegment};
+use crate::processor::{Breakpoint, StopReason};
+
+/// Number of logpoint messages kept while there is no log output.
+const LOG_LIMIT: usize = 1 << 10;
+
+impl Processor {
+    /// Toggles an unconditional breakpoint on a program memory address,
+    /// returning whether it is now set.
+    pub fn toggle_breakpoint(&mut self, line: usize) -> bool {
+        let location = self.symbolize(line);
+        match self.breakpoints.get_mut(line) {
+            Some(breakpoint) => {
+                *breakpoint = match breakpoint {
+                    Some(_) => None,
+                    None => Some(Breakpoint::new(location, None)),
+                };
+                breakpoint.is_some()
+            }
+            None => false,
+        }
+    }
+
+    /// Sets a breakpoint on a program memory address which only stops
+    /// execution if the condition evaluates to a non-zero value.
+    pub fn set_breakpoint(&mut self, line: usize, condition: Option<Expression>) -> bool {
+        let location = self.symbolize(line);
+        match self.breakpoints.get_mut(line) {
+            Some(breakpoint) => {
+                *breakpoint = Some(Breakpoint::new(location, condition));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets a logpoint on a program memory address which records a message
+    /// whenever the instruction there executes and the condition holds.
+    pub fn set_logpoint(
+        &mut self,
+        line: usize,
+        log: LogFormat,
+        condition: Option<Expression>,
+    ) -> bool {
+        let location = self.symbolize(line);
+        match self.breakpoints.get_mut(line) {
+            Some(breakpoint) => {
+                *breakpoint = Some(Breakpoint::logpoint(location, condition, log));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Formats a logpoint message using the current state, showing `?` for
+    /// expressions which cannot be evaluated.
+    pub fn format_log(&self, log: &LogFormat) -> String {
+        log.segments
+            .iter()
+            .map(|segment| match segment {
+                LogSegment::Text(text) => text.clone(),
+                LogSegment::Expression(expression) => match self.evaluate(expression) {
+                    Some(value) => value.to_string(),
+                    None => "?".into(),
+                },
+            })
+            .collect()
+    }
+
+    /// Writes logpoint messages to the output as soon as they are recorded,
+    /// instead of keeping them for [`Self::take_logs`].
+    pub fn set_log_output(&mut self, output: Option<Box<dyn Write>>) {
+        self.log_output = output;
+    }
+
+    /// Returns and clears the messages recorded by logpoints while there is
+    /// no log output, of which only the most recent 1024 are kept.
+    pub fn take_logs(&mut self) -> Vec<String> {
+        self.logs.drain(..).collect()
+    }
+
+    /// Makes the breakpoint on a program memory address ignore its next
+    /// `count` hits.
+    pub fn set_breakpoint_ignore(&mut self, line: usize, count: usize) -> bool {
+        match self.breakpoints.get_mut(line) {
+            Some(Some(breakpoint)) => {
+                breakpoint.ignore = count;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes all breakpoints.
+    pub fn clear_breakpoints(&mut self) -> &mut Self {
+        self.breakpoints.iter_mut().for_each(|b| *b = None);
+        self
+    }
+
+    /// Removes all breakpoints, returning them so they can be restored after
+    /// loading another program.
+    pub fn take_breakpoints(&mut self) -> Vec<Breakpoint> {
+        self.breakpoints
+            .iter_mut()
+            .filter_map(Option::take)
+            .collect()
+    }
+
+    /// Places breakpoints on their locations resolved using the current
+    /// labels, returning the ones which no longer resolve to a program
+    /// memory address.
+    pub fn restore_breakpoints(&mut self, breakpoints: Vec<Breakpoint>) -> Vec<Breakpoint> {
+        let mut unresolved = Vec::new();
+        for breakpoint in breakpoints {
+            match self.resolve(&breakpoint.location) {
+                Ok(line) => self.breakpoints[line] = Some(breakpoint),
+                Err(_) => unresolved.push(breakpoint),
+            }
+        }
+        unresolved
+    }
+
+    /// Describes a program memory address relative to the closest preceding
+    /// label, or as a plain address if there is none.
+    pub fn symbolize(&self, line: usize) -> Location {
+        self.labels
+            .iter()
+            .filter(|(&addr, _)| addr <= line)
+            .max_by_key(|(&addr, _)| addr)
+            .and_then(|(&addr, names)| {
+                let label = names.last()?.clone();
+                Some(Location::Label(label, (line - addr) as isize))
+            })
+            .unwrap_or(Location::Address(line))
+    }
+
+    /// Whether a breakpoint or logpoint is set on an address.
+    pub fn is_breakpoint(&self, line: usize) -> bool {
+        matches!(self.breakpoints.get(line), Some(Some(_)))
+    }
+
+    /// Whether a logpoint is set on an address.
+    pub fn is_logpoint(&self, line: usize) -> bool {
+        matches!(self.breakpoints.get(line), Some(Some(b)) if b.log.is_some())
+    }
+
+    /// Breakpoints and logpoints with their addresses, in address order.
+    pub fn breakpoints(&self) -> impl Iterator<Item = (usize, &Breakpoint)> {
+        self.breakpoints
+            .iter()
+            .enumerate()
+            .filter_map(|(line, breakpoint)| Some((line, breakpoint.as_ref()?)))
+    }
+
+    /// Whether the condition of the breakpoint on a program memory address
+    /// holds, or `None` if it cannot be evaluated.
+    pub(super) fn breakpoint_condition_met(&self, line: usize) -> Option<bool> {
+        match &self.breakpoints[line] {
+            Some(Breakpoint {
+                condition: Some(condition),
+                ..
+            }) => self.evaluate(condition).map(|value| value != 0),
+            Some(_) => Some(true),
+            None => Some(false),
+        }
+    }
+
+    /// Counts a hit of the breakpoint on a program memory address, returning
+    /// the reason if execution stops. A condition which cannot be evaluated
+    /// stops execution to report it.
+    pub(super) fn breakpoint_hit(&mut self, line: usize) -> Option<StopReason> {
+        match &self.breakpoints[line] {
+            Some(breakpoint) if breakpoint.log.is_none() => {}
+            _ => return None,
+        }
+        match self.breakpoint_condition_met(line) {
+            Some(true) => {}
+            Some(false) => return None,
+            None => return Some(StopReason::BreakpointCondition(line)),
+        }
+        let breakpoint = self.breakpoints[line].as_mut()?;
+        breakpoint.hits += 1;
+        if breakpoint.ignore > 0 {
+            breakpoint.ignore -= 1;
+            None
+        } else {
+            Some(StopReason::Breakpoint(line))
+        }
+    }
+
+    /// Counts a hit of the logpoint on a program memory address before the
+    /// instruction there executes, recording its message. Nothing is
+    /// recorded if the condition cannot be evaluated.
+    pub(super) fn logpoint_hit(&mut self, line: usize) {
+        let Some(Breakpoint { log: Some(log), .. }) = &self.breakpoints[line] else {
+            return;
+        };
+        if self.breakpoint_condition_met(line) != Some(true) {
+            return;
+        }
+        let message = self.format_log(log);
+        let Some(breakpoint) = self.breakpoints[line].as_mut() else {
+            return;
+        };
+        breakpoint.hits += 1;
+        if breakpoint.ignore > 0 {
+            breakpoint.ignore -= 1;
+            return;
+        }
+        if let Some(output) = &mut self.log_output {
+            if writeln!(output, "{message}").is_ok() {
+                return;
+            }
+        }
+        if self.logs.len() == LOG_LIMIT {
+            self.logs.pop_front();
+        }
+        self.logs.push_back(message);
+    }
+}
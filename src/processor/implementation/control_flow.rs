@@ -1,62 +1,32 @@
+use super::in_range;
 use super::Processor;
-use crate::in_range;
 
 use super::EmulationError;
 use crate::instructions::ControlFlowInstruction;
+use crate::processor::FlagRegisters;
+
+pub(super) fn jump_taken(op: ControlFlowInstruction, flags: FlagRegisters) -> bool {
+    match op {
+        ControlFlowInstruction::Jump(_) => true,
+        ControlFlowInstruction::JumpZero(_) => flags.zero,
+        ControlFlowInstruction::JumpSign(_) => flags.sign,
+        ControlFlowInstruction::JumpCarry(_) => flags.carry,
+        ControlFlowInstruction::JumpNotZero(_) => !flags.zero,
+        ControlFlowInstruction::JumpNotSign(_) => !flags.sign,
+        ControlFlowInstruction::JumpNotCarry(_) => !flags.carry,
+    }
+}
 
 impl Processor {
     pub(super) fn execute_control_flow(
         &mut self,
         op: ControlFlowInstruction,
     ) -> Result<(), EmulationError> {
-        match op {
-            ControlFlowInstruction::Jump(addr) => {
-                in_range![RAM_SIZE; addr];
-                self.program_counter = addr as usize;
-                Ok(())
-            }
-            ControlFlowInstruction::JumpZero(addr) => {
-                in_range![RAM_SIZE; addr];
-                if self.flags.zero {
-                    self.program_counter = addr as usize;
-                }
-                Ok(())
-            }
-            ControlFlowInstruction::JumpSign(addr) => {
-                in_range![RAM_SIZE; addr];
-                if self.flags.sign {
-                    self.program_counter = addr as usize;
-                }
-                Ok(())
-            }
-            ControlFlowInstruction::JumpCarry(addr) => {
-                in_range![RAM_SIZE; addr];
-                if self.flags.carry {
-                    self.program_counter = addr as usize;
-                }
-                Ok(())
-            }
-            ControlFlowInstruction::JumpNotZero(addr) => {
-                in_range![RAM_SIZE; addr];
-                if !self.flags.zero {
-                    self.program_counter = addr as usize;
-                }
-                Ok(())
-            }
-            ControlFlowInstruction::JumpNotSign(addr) => {
-                in_range![RAM_SIZE; addr];
-                if !self.flags.sign {
-                    self.program_counter = addr as usize;
-                }
-                Ok(())
-            }
-            ControlFlowInstruction::JumpNotCarry(addr) => {
-                in_range![RAM_SIZE; addr];
-                if !self.flags.carry {
-                    self.program_counter = addr as usize;
-                }
-                Ok(())
-            }
+        let addr = op.get_address();
+        in_range![self.rom.len(); addr];
+        if jump_taken(op, self.flags) {
+            self.program_counter = addr as usize;
         }
+        Ok(())
     }
 }
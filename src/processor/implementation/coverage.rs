@@ -0,0 +1,36 @@
+use super::Processor;
+
+use crate::instructions::Instruction;
+use crate::processor::{Coverage, CoverageEntry};
+
+impl Processor {
+    /// Collects execution counts of all instructions up to the end of the
+    /// program and directions taken by its conditional jumps.
+    pub fn coverage(&self) -> Coverage {
+        let entries = (0..self.last_instruction_address())
+            .map(|address| {
+                let instruction = self.rom[address];
+                let executions = self.executions[address];
+                let taken = self.jumps_taken[address];
+                let (target, branch) = match instruction {
+                    Instruction::ControlFlow(op) => (
+                        self.labels
+                            .get(&(op.get_address() as usize))
+                            .and_then(|labels| labels.last().cloned()),
+                        op.is_conditional().then_some((taken, executions - taken)),
+                    ),
+                    _ => (None, None),
+                };
+                CoverageEntry {
+                    address,
+                    labels: self.labels.get(&address).cloned().unwrap_or_default(),
+                    instruction,
+                    target,
+                    executions,
+                    branch,
+                }
+            })
+            .collect();
+        Coverage { entries }
+    }
+}
@@ -0,0 +1,26 @@
+use super::Processor;
+
+use crate::processor::CycleModel;
+
+impl Processor {
+    /// Number of clock cycles spent executing instructions since the last
+    /// reset, according to the cycle model.
+    pub fn cycle_counter(&self) -> usize {
+        self.cycle_counter
+    }
+
+    /// Clock cycles spent by each instruction class.
+    pub fn cycle_model(&self) -> CycleModel {
+        self.cycle_model
+    }
+
+    /// Replaces the cycle model used for instructions executed from now on.
+    pub fn set_cycle_model(&mut self, model: CycleModel) {
+        self.cycle_model = model;
+    }
+
+    /// Simulated execution time in seconds at the given clock frequency.
+    pub fn simulated_time(&self, frequency: f64) -> f64 {
+        self.cycle_counter as f64 / frequency
+    }
+}
@@ -1,16 +1,16 @@
 use super::Processor;
-use crate::{in_range, mem, reg};
+use super::{in_range, reg};
 
 use super::EmulationError;
 use crate::instructions::DebugInstruction;
-use crate::processor::ROM_SIZE;
+use crate::processor::Breakpoint;
 
 impl Processor {
     pub(super) fn execute_debug(&mut self, op: DebugInstruction) -> Result<(), EmulationError> {
         self.flags.unset();
         match op {
             DebugInstruction::SetRegister(z, v) => {
-                in_range![REG_COUNT; z];
+                in_range![self.registers.len(); z];
                 reg![self; z] = v;
                 Ok(())
             }
@@ -27,17 +27,20 @@ impl Processor {
                 Ok(())
             }
             DebugInstruction::SetMemory(addr, v) => {
-                in_range![RAM_SIZE; addr];
-                mem![self; addr] = v;
+                in_range![self.ram.len(); addr];
+                self.write_memory(addr as usize, v);
                 Ok(())
             }
             DebugInstruction::Breakpoint(addr) => {
-                in_range![RAM_SIZE; addr];
-                self.breakpoints[addr as usize] = true;
+                in_range![self.rom.len(); addr];
+                if self.breakpoints[addr as usize].is_none() {
+                    let location = self.symbolize(addr as usize);
+                    self.breakpoints[addr as usize] = Some(Breakpoint::new(location, None));
+                }
                 Ok(())
             }
             DebugInstruction::Halt => {
-                self.program_counter = ROM_SIZE;
+                self.program_counter = self.rom.len();
                 Ok(())
             }
         }
@@ -8,6 +8,7 @@ impl std::fmt::Display for Processor {
         self.print_flags(f)?;
         writeln!(f, "Program counter: {}", self.program_counter)?;
         writeln!(f, "Runtime counter: {}", self.runtime_counter)?;
+        writeln!(f, "Cycle counter: {}", self.cycle_counter)?;
         self.print_ram(f)?;
         self.print_rom(f)?;
         Ok(())
@@ -17,8 +18,15 @@ impl std::fmt::Display for Processor {
 impl Processor {
     fn print_registers(&self, f: &mut Formatter<'_>) -> Result {
         writeln!(f, "Registers")?;
+        let width = digits(self.registers.len() - 1);
         for i in 0..self.registers.len() {
-            write!(f, "| R{i}: {} ", self.print_value(self.registers[i]))?;
+            let name = format!("R{i}:");
+            write!(
+                f,
+                "| {name:<width$} {} ",
+                self.print_value(self.registers[i]),
+                width = width + 2
+            )?;
             if i != 0 && (i + 1) % 4 == 0 {
                 writeln!(f, "|")?;
             }
@@ -46,25 +54,41 @@ impl Processor {
                 .rev()
                 .map_while(|&i| if i == 0 { Some(()) } else { None })
                 .count();
+        let ram_max = self
+            .watchpoints
+            .iter()
+            .map(|w| w.address + 1)
+            .fold(ram_max, usize::max);
+        let width = digits(self.ram.len() - 1).max(3);
         for i in 0..ram_max {
-            writeln!(f, "| {:#3} | {}", i, self.print_value(self.ram[i]))?;
+            writeln!(
+                f,
+                "| {:width$} | {}{}",
+                i,
+                self.print_value(self.peek_memory(i).unwrap_or_default()),
+                self.watch_marker(i)
+            )?;
         }
         if ram_max < self.ram.len() {
-            writeln!(f, "| ··· | {}", self.print_value(0))?;
+            writeln!(f, "| {:>width$} | {}", "···", self.print_value(0))?;
+        }
+        for peripheral in &self.peripherals {
+            peripheral.render(f)?;
         }
         Ok(())
     }
 
     fn print_rom(&self, f: &mut Formatter<'_>) -> Result {
         let indent = if self.labels.is_empty() { "" } else { "    " };
+        let width = digits(self.rom.len() - 1).max(3);
         writeln!(f, "Program memory")?;
         for i in 0..self.last_instruction_address() {
             if let Some(labels) = self.labels.get(&i) {
                 for label in labels {
-                    writeln!(f, "|     | {label}:")?;
+                    writeln!(f, "| {:width$} | {label}:", "")?;
                 }
             }
-            write!(f, "| {i:#3} |{indent} {}", self.rom[i])?;
+            write!(f, "| {i:width$} |{indent} {}", self.rom[i])?;
             if let Instruction::ControlFlow(op) = self.rom[i] {
                 if let Some(labels) = self.labels.get(&(op.get_address() as usize)) {
                     write!(f, " ({})", labels[labels.len() - 1])?;
@@ -73,14 +97,14 @@ impl Processor {
             if self.program_counter == i {
                 write!(f, " <=")?;
             }
-            if self.breakpoints[i] {
-                writeln!(f, " (*)")?;
-            } else {
-                writeln!(f)?;
+            match &self.breakpoints[i] {
+                Some(breakpoint) if breakpoint.log.is_some() => writeln!(f, " (L)")?,
+                Some(_) => writeln!(f, " (*)")?,
+                None => writeln!(f)?,
             }
         }
         if self.last_instruction_address() < self.rom.len() {
-            write!(f, "| ··· | nop")?;
+            write!(f, "| {:>width$} | nop", "···")?;
             if self.program_counter >= self.last_instruction_address() {
                 writeln!(f, " <=")?;
             } else {
@@ -101,3 +125,7 @@ impl Processor {
         }
     }
 }
+
+fn digits(value: usize) -> usize {
+    value.checked_ilog10().unwrap_or(0) as usize + 1
+}
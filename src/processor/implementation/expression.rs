@@ -0,0 +1,98 @@
+use super::Processor;
+
+use crate::parser::{Expression, Operator};
+
+impl Processor {
+    /// Evaluates a debugger expression against the current state, returning
+    /// `None` if it refers to a register or memory cell out of range.
+    pub fn evaluate(&self, expression: &Expression) -> Option<i64> {
+        Some(match expression {
+            Expression::Literal(value) => *value,
+            Expression::Register(index) => *self.registers.get(*index as usize)? as i16 as i64,
+            Expression::Flag(flag) => self.flags.get(*flag) as i64,
+            Expression::Memory(address) => {
+                let address = usize::try_from(self.evaluate(address)?).ok()?;
+                self.peek_memory(address)? as i16 as i64
+            }
+            Expression::ProgramCounter => self.program_counter as i64,
+            Expression::RuntimeCounter => self.runtime_counter as i64,
+            Expression::Not(e) => (self.evaluate(e)? == 0) as i64,
+            Expression::Negate(e) => self.evaluate(e)?.wrapping_neg(),
+            Expression::Binary(op, lhs, rhs) => {
+                let lhs = self.evaluate(lhs)?;
+                match op {
+                    Operator::Or if lhs != 0 => 1,
+                    Operator::And if lhs == 0 => 0,
+                    _ => {
+                        let rhs = self.evaluate(rhs)?;
+                        match op {
+                            Operator::Or | Operator::And => (rhs != 0) as i64,
+                            Operator::Equal => (lhs == rhs) as i64,
+                            Operator::NotEqual => (lhs != rhs) as i64,
+                            Operator::Less => (lhs < rhs) as i64,
+                            Operator::LessEqual => (lhs <= rhs) as i64,
+                            Operator::Greater => (lhs > rhs) as i64,
+                            Operator::GreaterEqual => (lhs >= rhs) as i64,
+                            Operator::Add => lhs.wrapping_add(rhs),
+                            Operator::Subtract => lhs.wrapping_sub(rhs),
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse_expression;
+    use crate::processor::Processor;
+
+    fn evaluate(p: &Processor, text: &str) -> Option<i64> {
+        p.evaluate(&parse_expression(text).unwrap())
+    }
+
+    fn processor() -> Processor {
+        let mut p = Processor::new();
+        p.load_rom(&crate::asm![sreg 2, 65534; sreg 3, 300; sub 0, 0, 0])
+            .load_ram(&[0, 0, 0, 0, 0, 12]);
+        for _ in 0..3 {
+            p.tick().unwrap();
+        }
+        p
+    }
+
+    #[test]
+    fn registers_and_memory_are_signed() {
+        let p = processor();
+        assert_eq!(evaluate(&p, "R2"), Some(-2));
+        assert_eq!(evaluate(&p, "r2 == -2"), Some(1));
+        assert_eq!(evaluate(&p, "mem[5] > 10 && mem[R2 + 7] == 12"), Some(1));
+    }
+
+    #[test]
+    fn counters_and_flags_reflect_the_state() {
+        let p = processor();
+        assert_eq!(evaluate(&p, "pc"), Some(3));
+        assert_eq!(evaluate(&p, "runtime - 1"), Some(2));
+        assert_eq!(evaluate(&p, "zero"), Some(1));
+        assert_eq!(evaluate(&p, "!zero || sign"), Some(0));
+    }
+
+    #[test]
+    fn missing_cells_and_registers_cannot_be_evaluated() {
+        let p = processor();
+        assert_eq!(evaluate(&p, "mem[R3]"), None);
+        assert_eq!(evaluate(&p, "mem[-1]"), None);
+        assert_eq!(evaluate(&p, "R9"), None);
+    }
+
+    #[test]
+    fn logical_operators_short_circuit() {
+        let p = processor();
+        assert_eq!(evaluate(&p, "1 || mem[R3]"), Some(1));
+        assert_eq!(evaluate(&p, "0 && mem[R3]"), Some(0));
+        assert_eq!(evaluate(&p, "mem[R3] || 1"), None);
+        assert_eq!(evaluate(&p, "5 && 7"), Some(1));
+    }
+}
@@ -0,0 +1,162 @@
+use super::control_flow::jump_taken;
+use super::Processor;
+
+use crate::instructions::Instruction;
+use crate::processor::loop_detector::{cell_hash, mix};
+use crate::processor::StopReason;
+
+impl Processor {
+    /// Enables stopping runs which are stuck in a jump to itself or in a
+    /// loop which keeps repeating the same state.
+    pub fn set_loop_detection(&mut self, enabled: bool) {
+        self.detect_loops = enabled;
+    }
+
+    /// Whether runs stop on a detected infinite loop, which is the default.
+    pub fn loop_detection(&self) -> bool {
+        self.detect_loops
+    }
+
+    /// Checks the instruction just executed at `line` for a self-jump or a
+    /// backward jump returning to an earlier state of the current run.
+    pub(super) fn detect_loop(&mut self, line: usize) -> Option<StopReason> {
+        let Instruction::ControlFlow(jump) = self.rom[line] else {
+            return None;
+        };
+        let target = jump.get_address() as usize;
+        if target > line || !jump_taken(jump, self.flags) {
+            return None;
+        }
+        if target == line {
+            if Some(line) == self.end_address() {
+                return None;
+            }
+            return Some(StopReason::Loop {
+                start: line,
+                end: line,
+            });
+        }
+        if !self.loop_detector.sample() {
+            return None;
+        }
+        let since = self
+            .loop_detector
+            .check(self.state_hash(), self.runtime_counter)?;
+        let steps = self.runtime_counter - since;
+        let (start, end) = match self.journal.len() >= steps {
+            true => self
+                .journal
+                .recent(steps)
+                .fold((usize::MAX, 0), |(start, end), entry| {
+                    let line = entry.program_counter as usize;
+                    (start.min(line), end.max(line))
+                }),
+            false => (target, line),
+        };
+        Some(StopReason::Loop { start, end })
+    }
+
+    fn state_hash(&self) -> u64 {
+        let flags =
+            self.flags.zero as u64 | (self.flags.sign as u64) << 1 | (self.flags.carry as u64) << 2;
+        let mut hash = (self.program_counter as u64) << 3 | flags;
+        for &value in &self.registers {
+            hash = hash.wrapping_mul(0x1_0000_01b3) ^ value as u64;
+        }
+        hash = mix(hash) ^ self.ram_hash;
+        for peripheral in &self.peripherals {
+            for address in peripheral.range() {
+                hash = mix(hash ^ cell_hash(address, peripheral.peek(address)));
+            }
+        }
+        hash
+    }
+
+    pub(super) fn rehash_ram(&mut self) {
+        self.ram_hash = self
+            .ram
+            .iter()
+            .enumerate()
+            .fold(0, |hash, (address, &value)| {
+                hash ^ cell_hash(address, value)
+            });
+    }
+
+    pub(super) fn store_ram(&mut self, address: usize, value: u16) {
+        let cell = &mut self.ram[address];
+        self.ram_hash ^= cell_hash(address, *cell) ^ cell_hash(address, value);
+        *cell = value;
+    }
+
+    /// Whether the program counter stays where it is because the instruction
+    /// there is a taken jump to itself.
+    pub(super) fn parked(&self) -> bool {
+        matches!(
+            self.rom.get(self.program_counter),
+            Some(Instruction::ControlFlow(jump))
+                if jump.get_address() as usize == self.program_counter
+                    && jump_taken(*jump, self.flags)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instructions::Instruction;
+    use crate::processor::{Processor, RunOutcome, StopReason};
+
+    fn run(program: &[Instruction], history: usize) -> RunOutcome {
+        let mut p = Processor::new();
+        p.set_history_limit(history);
+        p.load_rom(program);
+        p.run(false, None)
+    }
+
+    #[test]
+    fn jump_to_itself_before_the_end_is_a_loop() {
+        let outcome = run(&crate::asm![jmp 0; inc 0, 0], 16);
+        assert!(matches!(
+            outcome,
+            RunOutcome::Stopped(StopReason::Loop { start: 0, end: 0 })
+        ));
+    }
+
+    #[test]
+    fn jump_to_itself_at_the_end_halts() {
+        let outcome = run(&crate::asm![inc 0, 0; jmp 1], 16);
+        assert!(matches!(outcome, RunOutcome::Halted));
+    }
+
+    #[test]
+    fn repeating_state_is_a_loop_with_or_without_history() {
+        let program = crate::asm![inc 1, 1; inc 0, 0; dec 0, 0; jmpz 1; halt];
+        for history in [0, 16] {
+            assert!(matches!(
+                run(&program, history),
+                RunOutcome::Stopped(StopReason::Loop { start: 1, end: 3 })
+            ));
+        }
+    }
+
+    #[test]
+    fn changing_state_is_not_a_loop() {
+        let program = crate::asm![inc 0, 0; st 0, 1; jmpnz 0; halt];
+        let mut p = Processor::new();
+        p.load_rom(&program);
+        assert!(matches!(p.run(false, None), RunOutcome::Halted));
+        assert_eq!(p.runtime_counter(), 3 * 65536 + 1);
+    }
+
+    #[test]
+    fn disabled_detection_runs_into_the_step_limit() {
+        let mut p = Processor::new();
+        p.load_rom(&crate::asm![jmp 0; inc 0, 0]);
+        p.set_loop_detection(false);
+        p.set_step_limit(Some(100));
+        assert!(matches!(
+            p.run(false, None),
+            RunOutcome::Stopped(StopReason::StepLimit(100))
+        ));
+        assert_eq!(p.runtime_counter(), 100);
+    }
+}
@@ -1,25 +1,74 @@
 use super::Processor;
-use crate::{in_range, mem, reg};
+use super::{in_range, mem, reg};
 
 use super::EmulationError;
-use crate::instructions::MemoryInstruction;
+use crate::instructions::{DebugInstruction, Instruction, MemoryInstruction};
 
 impl Processor {
+    /// Data memory address the instruction would access in the current
+    /// state, along with the value it would write if it is a store.
+    pub(super) fn memory_access(&self, op: Instruction) -> Option<(usize, Option<u16>)> {
+        match op {
+            Instruction::Memory(MemoryInstruction::Load(_, y)) => {
+                Some((*self.registers.get(y as usize)? as usize, None))
+            }
+            Instruction::Memory(MemoryInstruction::Store(x, y)) => Some((
+                *self.registers.get(y as usize)? as usize,
+                Some(*self.registers.get(x as usize)?),
+            )),
+            Instruction::Debug(DebugInstruction::SetMemory(addr, v)) => {
+                Some((addr as usize, Some(v)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Value of a data memory address as seen by the program, without side
+    /// effects on peripherals.
+    pub fn peek_memory(&self, address: usize) -> Option<u16> {
+        match self.peripheral(address) {
+            Some(peripheral) => Some(peripheral.peek(address)),
+            None => self.ram.get(address).copied(),
+        }
+    }
+
+    pub(super) fn read_memory(&mut self, address: usize) -> u16 {
+        match self.peripheral_mut(address) {
+            Some(peripheral) => peripheral.read(address),
+            None => mem![self; address],
+        }
+    }
+
+    pub(super) fn write_memory(&mut self, address: usize, value: u16) {
+        match self.peripheral_mut(address) {
+            Some(peripheral) => peripheral.write(address, value),
+            None => self.store_ram(address, value),
+        }
+    }
+
+    /// Puts back a value stored to a peripheral, unless the peripheral has
+    /// been removed since.
+    pub(super) fn restore_peripheral(&mut self, address: usize, value: u16) {
+        if let Some(peripheral) = self.peripheral_mut(address) {
+            peripheral.restore(address, value);
+        }
+    }
+
     pub(super) fn execute_memory(&mut self, op: MemoryInstruction) -> Result<(), EmulationError> {
         self.flags.unset();
         match op {
             MemoryInstruction::Load(z, y) => {
-                in_range![REG_COUNT; z, y];
-                in_range![RAM_SIZE; reg![self; y]];
-                reg![self; z] = mem![self; reg![self; y]];
+                in_range![self.registers.len(); z, y];
+                in_range![self.ram.len(); reg![self; y]];
+                reg![self; z] = self.read_memory(reg![self; y] as usize);
                 self.flags.zero = reg![self; z] == 0;
                 self.flags.sign = reg![self; z] & 0x8000 != 0;
                 Ok(())
             }
             MemoryInstruction::Store(x, y) => {
-                in_range![REG_COUNT; x, y];
-                in_range![RAM_SIZE; reg![self; y]];
-                mem![self; reg![self; y]] = reg![self; x];
+                in_range![self.registers.len(); x, y];
+                in_range![self.ram.len(); reg![self; y]];
+                self.write_memory(reg![self; y] as usize, reg![self; x]);
                 self.flags.zero = reg![self; x] == 0;
                 self.flags.sign = reg![self; x] & 0x8000 != 0;
                 Ok(())
@@ -0,0 +1,74 @@
+use super::Processor;
+
+use crate::peripheral::Peripheral;
+
+impl Processor {
+    /// Attaches a peripheral unless its range is empty, exceeds the data
+    /// memory or overlaps another peripheral.
+    pub fn add_peripheral(&mut self, mut peripheral: Box<dyn Peripheral>) -> bool {
+        let range = peripheral.range();
+        if range.is_empty() || range.end > self.ram.len() {
+            return false;
+        }
+        if self.peripherals.iter().any(|p| {
+            let other = p.range();
+            range.start < other.end && other.start < range.end
+        }) {
+            return false;
+        }
+        peripheral.tick(self.runtime_counter, self.cycle_counter);
+        peripheral.set_history_limit(self.journal.limit());
+        self.peripherals.push(peripheral);
+        true
+    }
+
+    /// Detaches the peripheral handling a data memory address.
+    pub fn remove_peripheral(&mut self, address: usize) -> Option<Box<dyn Peripheral>> {
+        let index = self
+            .peripherals
+            .iter()
+            .position(|p| p.range().contains(&address))?;
+        Some(self.peripherals.remove(index))
+    }
+
+    /// Detaches every peripheral.
+    pub fn clear_peripherals(&mut self) {
+        self.peripherals.clear();
+    }
+
+    /// Attached peripherals in the order they were added.
+    pub fn peripherals(&self) -> impl Iterator<Item = &dyn Peripheral> {
+        self.peripherals.iter().map(|p| p.as_ref())
+    }
+
+    /// Peripheral of a concrete type handling a data memory address.
+    pub fn peripheral_as_mut<T: Peripheral + 'static>(&mut self, address: usize) -> Option<&mut T> {
+        self.peripheral_mut(address)?
+            .as_mut()
+            .as_any_mut()
+            .downcast_mut::<T>()
+    }
+
+    /// First peripheral of a concrete type.
+    pub fn find_peripheral_mut<T: Peripheral + 'static>(&mut self) -> Option<&mut T> {
+        self.peripherals
+            .iter_mut()
+            .find_map(|p| p.as_mut().as_any_mut().downcast_mut::<T>())
+    }
+
+    pub(super) fn peripheral(&self, address: usize) -> Option<&dyn Peripheral> {
+        self.peripherals().find(|p| p.range().contains(&address))
+    }
+
+    pub(super) fn peripheral_mut(&mut self, address: usize) -> Option<&mut Box<dyn Peripheral>> {
+        self.peripherals
+            .iter_mut()
+            .find(|p| p.range().contains(&address))
+    }
+
+    pub(super) fn tick_peripherals(&mut self) {
+        for peripheral in &mut self.peripherals {
+            peripheral.tick(self.runtime_counter, self.cycle_counter);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use super::Processor;
+
+use crate::processor::{Profile, ProfileEntry, ProfileGroup};
+
+impl Processor {
+    /// Number of times each program memory address was executed since the
+    /// last reset.
+    pub fn executions(&self) -> &[usize] {
+        &self.executions
+    }
+
+    /// Groups executed addresses by their preceding label, sorting both
+    /// groups and addresses by execution count.
+    pub fn profile(&self) -> Profile {
+        let mut groups = BTreeMap::<Option<usize>, ProfileGroup>::new();
+        for (address, &count) in self.executions.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let start = self.labels.keys().filter(|&&addr| addr <= address).max();
+            let group = groups
+                .entry(start.copied())
+                .or_insert_with(|| ProfileGroup {
+                    label: start.and_then(|addr| self.labels[addr].last().cloned()),
+                    count: 0,
+                    entries: Vec::new(),
+                });
+            group.count += count;
+            group.entries.push(ProfileEntry {
+                address,
+                instruction: self.rom[address],
+                count,
+            });
+        }
+        let mut groups = groups.into_values().collect::<Vec<_>>();
+        groups.sort_by_key(|group| Reverse(group.count));
+        for group in &mut groups {
+            group.entries.sort_by_key(|entry| Reverse(entry.count));
+        }
+        Profile {
+            total: self.runtime_counter,
+            groups,
+        }
+    }
+}
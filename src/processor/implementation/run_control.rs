@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::Processor;
+
+use crate::processor::RunOutcome;
+
+impl Processor {
+    /// Shares a flag which stops the current run once it is set, such as
+    /// from a Ctrl-C handler. Every run clears the flag when it starts, so
+    /// only setting it during the run stops it.
+    pub fn set_interrupt(&mut self, flag: Arc<AtomicBool>) {
+        self.interrupt = Some(flag);
+    }
+
+    /// Limits how many instructions a single run executes, unless a smaller
+    /// count is given to it.
+    pub fn set_step_limit(&mut self, limit: Option<usize>) {
+        self.step_limit = limit;
+    }
+
+    /// Most instructions a single run executes, if limited.
+    pub fn step_limit(&self) -> Option<usize> {
+        self.step_limit
+    }
+
+    pub(super) fn clear_interrupt(&self) {
+        if let Some(flag) = &self.interrupt {
+            flag.store(false, Ordering::Relaxed);
+        }
+    }
+
+    pub(super) fn interrupted(&self) -> bool {
+        self.interrupt
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed) && flag.swap(false, Ordering::Relaxed))
+    }
+
+    /// Classifies a run which reached the end of the program. A `halt`,
+    /// which moves the program counter out of program memory, or a taken
+    /// jump to itself ends it, anything else falls off.
+    pub(super) fn end_outcome(&self) -> RunOutcome {
+        if self.program_counter >= self.rom.len() || self.parked() {
+            RunOutcome::Halted
+        } else {
+            RunOutcome::FellOffEnd
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use super::Processor;
+
+use crate::instructions::Instruction;
+use crate::processor::journal::Change;
+use crate::trace::{TraceFormat, TraceRecord, Tracer};
+
+impl Processor {
+    /// Starts writing a record of every executed instruction to the output.
+    pub fn start_trace(
+        &mut self,
+        output: Box<dyn std::io::Write>,
+        format: TraceFormat,
+    ) -> std::io::Result<()> {
+        self.stop_trace()?;
+        self.tracer = Some(Tracer::new(output, format)?);
+        Ok(())
+    }
+
+    /// Stops tracing, returning whether a trace was being recorded.
+    pub fn stop_trace(&mut self) -> std::io::Result<bool> {
+        match self.tracer.take() {
+            Some(mut tracer) => tracer.flush().map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Whether executed instructions are written to a trace.
+    pub fn is_tracing(&self) -> bool {
+        self.tracer.is_some()
+    }
+
+    /// Error which stopped tracing when a record could not be written. The
+    /// instruction it belonged to still executed, so only the trace is cut
+    /// short.
+    pub fn take_trace_error(&mut self) -> Option<std::io::Error> {
+        self.trace_error.take()
+    }
+
+    pub(super) fn trace_record(
+        &self,
+        program_counter: usize,
+        op: Instruction,
+        change: Change,
+        access: Option<(usize, Option<u16>)>,
+    ) -> TraceRecord {
+        TraceRecord {
+            step: self.runtime_counter,
+            program_counter,
+            instruction: op,
+            register_write: match change {
+                Change::Register(index, _) => Some((index, self.registers[index as usize])),
+                _ => None,
+            },
+            memory_read: match access {
+                Some((address, None)) => match change {
+                    Change::Register(index, _) => Some((address, self.registers[index as usize])),
+                    _ => None,
+                },
+                _ => None,
+            },
+            memory_write: match access {
+                Some((address, Some(value))) => Some((address, value)),
+                _ => None,
+            },
+            flags: self.flags,
+        }
+    }
+}
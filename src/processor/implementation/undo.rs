@@ -0,0 +1,255 @@
+use super::control_flow::jump_taken;
+use super::Processor;
+
+use crate::instructions::{DebugInstruction, Instruction, MemoryInstruction};
+use crate::processor::journal::{Change, JournalEntry};
+use crate::processor::StopReason;
+
+impl Processor {
+    /// State the instruction is about to overwrite.
+    pub(super) fn journal_change(&self, op: Instruction) -> Change {
+        match op {
+            Instruction::Alu(op) => self.register_change(op.get_destination()),
+            Instruction::Memory(MemoryInstruction::Load(z, _)) => self.register_change(z),
+            Instruction::Memory(MemoryInstruction::Store(_, y)) => {
+                match self.registers.get(y as usize) {
+                    Some(&addr) => self.memory_change(addr),
+                    None => Change::None,
+                }
+            }
+            Instruction::Debug(DebugInstruction::SetRegister(z, _)) => self.register_change(z),
+            Instruction::Debug(DebugInstruction::SetMemory(addr, _)) => self.memory_change(addr),
+            Instruction::Debug(DebugInstruction::Breakpoint(addr)) => {
+                match self.breakpoints.get(addr as usize) {
+                    Some(None) => Change::Breakpoint(addr),
+                    _ => Change::None,
+                }
+            }
+            _ => Change::None,
+        }
+    }
+
+    fn register_change(&self, index: u8) -> Change {
+        match self.registers.get(index as usize) {
+            Some(&value) => Change::Register(index, value),
+            None => Change::None,
+        }
+    }
+
+    fn memory_change(&self, addr: u16) -> Change {
+        if let Some(peripheral) = self.peripheral(addr as usize) {
+            return Change::Peripheral(addr, peripheral.peek(addr as usize));
+        }
+        match self.ram.get(addr as usize) {
+            Some(&value) => Change::Memory(addr, value),
+            None => Change::None,
+        }
+    }
+
+    /// Reverts up to `count` most recently executed instructions, returning
+    /// how many were reverted before the history ran out.
+    pub fn undo(&mut self, count: usize) -> usize {
+        self.stop = None;
+        for undone in 0..count {
+            match self.journal.pop() {
+                Some(entry) => self.revert(entry),
+                None => return undone,
+            }
+        }
+        count
+    }
+
+    /// Reverts instructions until the program counter reaches a breakpoint
+    /// or an instruction which triggers a watchpoint, register watch or flag
+    /// watch, returning how many were reverted. Breakpoints still ignoring
+    /// hits are passed, counting the hit as when running forward.
+    pub fn reverse_continue(&mut self) -> usize {
+        self.stop = None;
+        let mut undone = 0;
+        while let Some(entry) = self.journal.pop() {
+            let hit = self.register_watch_hit(entry.change, entry.flags);
+            self.revert(entry);
+            undone += 1;
+            self.stop = hit.or_else(|| self.reverse_stop_reason());
+            if self.stop.is_some() {
+                break;
+            }
+        }
+        undone
+    }
+
+    fn reverse_stop_reason(&mut self) -> Option<StopReason> {
+        let pc = self.program_counter;
+        self.watchpoint_hit(*self.rom.get(pc)?)
+            .or_else(|| self.breakpoint_hit(pc))
+    }
+
+    /// Reverts instructions until the program counter reaches the given
+    /// label, or any label if none is given. Returns how many instructions
+    /// were reverted, or `None` if the label is not defined.
+    pub fn reverse_to_label(&mut self, label: Option<&str>) -> Option<usize> {
+        self.stop = None;
+        match label {
+            Some(label) => {
+                let addr = self.label_address(label)?;
+                Some(self.reverse_until(|p| p.program_counter == addr))
+            }
+            None => Some(self.reverse_until(|p| p.labels.contains_key(&p.program_counter))),
+        }
+    }
+
+    fn reverse_until(&mut self, stop: impl Fn(&Self) -> bool) -> usize {
+        let mut undone = 0;
+        while let Some(entry) = self.journal.pop() {
+            self.revert(entry);
+            undone += 1;
+            if stop(self) {
+                break;
+            }
+        }
+        undone
+    }
+
+    fn revert(&mut self, entry: JournalEntry) {
+        self.runtime_counter -= 1;
+        self.cycle_counter -= entry.cycles as usize;
+        if !self.peripherals.is_empty() {
+            self.tick_peripherals();
+        }
+        match entry.change {
+            Change::None => {}
+            Change::Register(index, value) => self.registers[index as usize] = value,
+            Change::Memory(addr, value) => self.store_ram(addr as usize, value),
+            Change::Peripheral(addr, value) => self.restore_peripheral(addr as usize, value),
+            Change::Breakpoint(addr) => self.breakpoints[addr as usize] = None,
+        }
+        let line = entry.program_counter as usize;
+        self.flags = entry.flags;
+        self.program_counter = line;
+        self.executions[line] -= 1;
+        if let Instruction::ControlFlow(jump) = self.rom[line] {
+            if jump_taken(jump, entry.flags) {
+                self.jumps_taken[line] -= 1;
+            }
+        }
+    }
+
+    /// Sets how many executed instructions can be undone, discarding the
+    /// oldest ones if the history is already longer.
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.journal.set_limit(limit);
+        for peripheral in &mut self.peripherals {
+            peripheral.set_history_limit(limit);
+        }
+    }
+
+    /// Number of executed instructions which can be undone at most.
+    pub fn history_limit(&self) -> usize {
+        self.journal.limit()
+    }
+
+    pub(super) fn clear_history(&mut self) {
+        self.journal.clear();
+        for peripheral in &mut self.peripherals {
+            peripheral.clear_history();
+        }
+    }
+
+    /// Number of executed instructions which can be undone now.
+    pub fn history_len(&self) -> usize {
+        self.journal.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::processor::{FlagRegisters, Processor};
+
+    type State = (Vec<u16>, Vec<u16>, FlagRegisters, usize, usize, usize);
+
+    fn state(p: &Processor) -> State {
+        (
+            p.registers().to_vec(),
+            p.ram().to_vec(),
+            p.flags(),
+            p.program_counter(),
+            p.runtime_counter(),
+            p.cycle_counter(),
+        )
+    }
+
+    /// Multiplies `mem[1]` by `mem[2]` into `mem[0]`, returning the state
+    /// before every executed instruction and the final one.
+    fn multiply(p: &mut Processor) -> Vec<State> {
+        p.load_rom(&crate::asm![
+            inc 0, 0; ld 1, 0; inc 0, 0; ld 2, 0; sub 0, 0, 0;
+            add 0, 0, 1; dec 2, 2; jmpnz 5; st 0, 2; jmp 9
+        ])
+        .load_ram(&[0, 5, 6]);
+        let mut states = vec![state(p)];
+        while !p.finished() {
+            p.tick().unwrap();
+            states.push(state(p));
+        }
+        states
+    }
+
+    #[test]
+    fn undo_steps_back_through_every_state() {
+        let mut p = Processor::new();
+        let mut states = multiply(&mut p);
+        assert_eq!(p.ram()[0], 30);
+        states.pop();
+        while let Some(expected) = states.pop() {
+            assert_eq!(p.undo(1), 1);
+            assert_eq!(state(&p), expected);
+        }
+        assert_eq!(p.undo(1), 0);
+    }
+
+    #[test]
+    fn undo_count_stops_when_history_runs_out() {
+        let mut p = Processor::new();
+        let states = multiply(&mut p);
+        let executed = states.len() - 1;
+        assert_eq!(p.history_len(), executed);
+        assert_eq!(p.undo(3), 3);
+        assert_eq!(state(&p), states[executed - 3]);
+        assert_eq!(p.undo(executed), executed - 3);
+        assert_eq!(state(&p), states[0]);
+    }
+
+    #[test]
+    fn history_limit_keeps_most_recent_instructions() {
+        let mut p = Processor::new();
+        p.set_history_limit(4);
+        let states = multiply(&mut p);
+        let executed = states.len() - 1;
+        assert_eq!(p.history_len(), 4);
+        assert_eq!(p.undo(10), 4);
+        assert_eq!(state(&p), states[executed - 4]);
+        p.set_history_limit(2);
+        assert_eq!(p.history_len(), 0);
+    }
+
+    #[test]
+    fn history_limit_shrinks_existing_history() {
+        let mut p = Processor::new();
+        let states = multiply(&mut p);
+        let executed = states.len() - 1;
+        p.set_history_limit(2);
+        assert_eq!(p.history_len(), 2);
+        assert_eq!(p.undo(3), 2);
+        assert_eq!(state(&p), states[executed - 2]);
+    }
+
+    #[test]
+    fn zero_history_limit_records_nothing() {
+        let mut p = Processor::new();
+        p.set_history_limit(0);
+        multiply(&mut p);
+        assert_eq!(p.history_len(), 0);
+        assert_eq!(p.undo(1), 0);
+        assert_eq!(p.ram()[0], 30);
+    }
+}
@@ -0,0 +1,166 @@
+use super::Processor;
+
+use crate::instructions::Instruction;
+use crate::processor::journal::Change;
+use crate::processor::{
+    Flag, FlagRegisters, RegisterWatch, RegisterWatchKind, StopReason, WatchKind, Watchpoint,
+};
+
+impl Processor {
+    /// Adds a watchpoint on a data memory address, returning `false` if the
+    /// address is out of range.
+    pub fn add_watchpoint(&mut self, address: usize, kind: WatchKind) -> bool {
+        if address >= self.ram.len() {
+            return false;
+        }
+        let watchpoint = Watchpoint { address, kind };
+        if !self.watchpoints.contains(&watchpoint) {
+            self.watchpoints.push(watchpoint);
+        }
+        true
+    }
+
+    /// Removes watchpoints on a data memory address, either of the given kind
+    /// or all of them, returning whether any were removed.
+    pub fn remove_watchpoint(&mut self, address: usize, kind: Option<WatchKind>) -> bool {
+        let count = self.watchpoints.len();
+        self.watchpoints
+            .retain(|w| w.address != address || kind.is_some_and(|kind| w.kind != kind));
+        self.watchpoints.len() != count
+    }
+
+    /// Removes every watchpoint.
+    pub fn clear_watchpoints(&mut self) -> &mut Self {
+        self.watchpoints.clear();
+        self
+    }
+
+    /// Watchpoints in the order they were set.
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// Adds a watch on writes to a register, returning `false` if the
+    /// register does not exist.
+    pub fn add_register_watch(&mut self, register: u8, kind: RegisterWatchKind) -> bool {
+        if register as usize >= self.registers.len() {
+            return false;
+        }
+        let watch = RegisterWatch { register, kind };
+        if !self.register_watches.contains(&watch) {
+            self.register_watches.push(watch);
+        }
+        true
+    }
+
+    /// Removes watches on a register, either of the given kind or all of
+    /// them, returning whether any were removed.
+    pub fn remove_register_watch(&mut self, register: u8, kind: Option<RegisterWatchKind>) -> bool {
+        let count = self.register_watches.len();
+        self.register_watches
+            .retain(|w| w.register != register || kind.is_some_and(|kind| w.kind != kind));
+        self.register_watches.len() != count
+    }
+
+    /// Register watches in the order they were set.
+    pub fn register_watches(&self) -> &[RegisterWatch] {
+        &self.register_watches
+    }
+
+    /// Toggles the watch on flips of a flag, returning whether it is set.
+    pub fn toggle_flag_watch(&mut self, flag: Flag) -> bool {
+        if let Some(index) = self.flag_watches.iter().position(|&f| f == flag) {
+            self.flag_watches.remove(index);
+            false
+        } else {
+            self.flag_watches.push(flag);
+            true
+        }
+    }
+
+    /// Watched flags in the order they were set.
+    pub fn flag_watches(&self) -> &[Flag] {
+        &self.flag_watches
+    }
+
+    /// Removes watchpoints and register and flag watches.
+    pub fn clear_all_watches(&mut self) -> &mut Self {
+        self.register_watches.clear();
+        self.flag_watches.clear();
+        self.clear_watchpoints()
+    }
+
+    /// Reason the last step, run or reverse run stopped, if it was caused by
+    /// a breakpoint or a watchpoint.
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop
+    }
+
+    pub(super) fn watchpoint_hit(&self, op: Instruction) -> Option<StopReason> {
+        if self.watchpoints.is_empty() {
+            return None;
+        }
+        let (address, write) = self.memory_access(op)?;
+        let old = self.peek_memory(address)?;
+        self.watchpoints.iter().find_map(|&watchpoint| {
+            if watchpoint.address != address {
+                return None;
+            }
+            let new = match (watchpoint.kind, write) {
+                (WatchKind::Read, None) => old,
+                (WatchKind::Write, Some(new)) => new,
+                (WatchKind::Change, Some(new)) if new != old => new,
+                _ => return None,
+            };
+            Some(StopReason::Watchpoint {
+                watchpoint,
+                old,
+                new,
+            })
+        })
+    }
+
+    /// Register or flag watch triggered by an executed instruction, given
+    /// the register it overwrote and the flags before it.
+    pub(super) fn register_watch_hit(
+        &self,
+        change: Change,
+        flags: FlagRegisters,
+    ) -> Option<StopReason> {
+        if self.register_watches.is_empty() && self.flag_watches.is_empty() {
+            return None;
+        }
+        if let Change::Register(register, old) = change {
+            let new = self.registers[register as usize];
+            let hit = self
+                .register_watches
+                .iter()
+                .find(|w| w.register == register && w.kind.triggered(old, new));
+            if let Some(&watch) = hit {
+                return Some(StopReason::RegisterWatch { watch, old, new });
+            }
+        }
+        self.flag_watches.iter().find_map(|&flag| {
+            let value = self.flags.get(flag);
+            (flags.get(flag) != value).then_some(StopReason::FlagWatch { flag, value })
+        })
+    }
+
+    pub(super) fn watch_marker(&self, address: usize) -> String {
+        let kinds: String = self
+            .watchpoints
+            .iter()
+            .filter(|w| w.address == address)
+            .map(|w| match w.kind {
+                WatchKind::Read => 'r',
+                WatchKind::Write => 'w',
+                WatchKind::Change => 'c',
+            })
+            .collect();
+        if kinds.is_empty() {
+            kinds
+        } else {
+            format!(" ({kinds})")
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::VecDeque;
+
+use super::FlagRegisters;
+
+/// Default number of executed instructions which can be undone.
+pub const HISTORY_LIMIT: usize = 1 << 18;
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Change {
+    None,
+    Register(u8, u16),
+    /// Data memory cell, restored even if a peripheral maps it by now.
+    Memory(u16, u16),
+    /// Address handled by a peripheral, skipped once no peripheral maps it.
+    Peripheral(u16, u16),
+    Breakpoint(u16),
+}
+
+/// State overwritten by one executed instruction. Program and data memory
+/// addresses always fit in 16 bits, keeping the entry at 16 bytes.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct JournalEntry {
+    pub program_counter: u16,
+    pub flags: FlagRegisters,
+    pub change: Change,
+    pub cycles: u32,
+}
+
+#[derive(Debug)]
+pub(super) struct Journal {
+    entries: VecDeque<JournalEntry>,
+    limit: usize,
+}
+
+impl Journal {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            limit,
+        }
+    }
+
+    /// Whether executed instructions are recorded at all.
+    pub fn enabled(&self) -> bool {
+        self.limit > 0
+    }
+
+    pub fn push(&mut self, entry: JournalEntry) {
+        if self.limit == 0 {
+            return;
+        }
+        if self.entries.len() == self.limit {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub fn pop(&mut self) -> Option<JournalEntry> {
+        self.entries.pop_back()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The `count` most recent entries, oldest first.
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = &JournalEntry> {
+        self.entries
+            .range(self.entries.len().saturating_sub(count)..)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        if self.entries.len() > limit {
+            self.entries.drain(..self.entries.len() - limit);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+/// Number of backward jumps between state comparisons, which keeps tight
+/// loops fast while still detecting every repeating state eventually.
+const SAMPLE_INTERVAL: usize = 16;
+
+/// Detects a repeating processor state using Brent's algorithm, comparing
+/// the state after every few backward jumps against a checkpoint which
+/// moves after exponentially growing intervals.
+#[derive(Debug, Default)]
+pub(super) struct LoopDetector {
+    checkpoint: Option<(u64, usize)>,
+    power: usize,
+    length: usize,
+    jumps: usize,
+}
+
+impl LoopDetector {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Counts a backward jump, returning whether the state after it should
+    /// be checked.
+    pub fn sample(&mut self) -> bool {
+        self.jumps += 1;
+        self.jumps % SAMPLE_INTERVAL == 0
+    }
+
+    /// Records a state hash, returning the runtime counter at which the
+    /// same state was seen before.
+    pub fn check(&mut self, state: u64, runtime: usize) -> Option<usize> {
+        match self.checkpoint {
+            Some((hash, since)) if hash == state => return Some(since),
+            Some(_) if self.length < self.power => self.length += 1,
+            _ => {
+                self.checkpoint = Some((state, runtime));
+                self.power = (self.power * 2).max(1);
+                self.length = 0;
+            }
+        }
+        None
+    }
+}
+
+/// Scrambles a value so that XOR and chained combinations of the results
+/// make good hashes.
+pub(super) fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Hash of a data memory cell, combined with XOR into the memory hash.
+pub(super) fn cell_hash(address: usize, value: u16) -> u64 {
+    mix((address as u64) << 16 | value as u64)
+}
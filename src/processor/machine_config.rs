@@ -0,0 +1,112 @@
+use std::fmt::{Display, Formatter, Result};
+use std::str::FromStr;
+
+use super::{RAM_SIZE, REG_COUNT, ROM_SIZE};
+
+/// Sizes of the program memory, data memory and register file, defaulting
+/// to those of the reference LPRS1 CPU. Only created through [`Self::new`]
+/// or parsing, so the sizes are always valid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MachineConfig {
+    rom_size: usize,
+    ram_size: usize,
+    register_count: usize,
+}
+
+impl Default for MachineConfig {
+    fn default() -> Self {
+        Self {
+            rom_size: ROM_SIZE,
+            ram_size: RAM_SIZE,
+            register_count: REG_COUNT,
+        }
+    }
+}
+
+impl MachineConfig {
+    /// Creates a configuration unless a memory is larger than the 16-bit
+    /// address space or there are more registers than fit into an
+    /// instruction.
+    pub fn new(rom_size: usize, ram_size: usize, register_count: usize) -> Option<Self> {
+        let words = 1..=1 << 16;
+        if words.contains(&rom_size)
+            && words.contains(&ram_size)
+            && (1..=1 << 8).contains(&register_count)
+        {
+            Some(Self {
+                rom_size,
+                ram_size,
+                register_count,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Number of instructions in the program memory.
+    pub fn rom_size(&self) -> usize {
+        self.rom_size
+    }
+
+    /// Number of words in the data memory.
+    pub fn ram_size(&self) -> usize {
+        self.ram_size
+    }
+
+    /// Number of registers.
+    pub fn register_count(&self) -> usize {
+        self.register_count
+    }
+
+    /// Width of a program memory address.
+    pub fn rom_address_bits(&self) -> usize {
+        bits(self.rom_size)
+    }
+
+    /// Width of a data memory address.
+    pub fn ram_address_bits(&self) -> usize {
+        bits(self.ram_size)
+    }
+
+    /// Width of a register index.
+    pub fn register_bits(&self) -> usize {
+        bits(self.register_count)
+    }
+
+    /// Width of an encoded instruction, with the operands wide enough for
+    /// either three registers or a program memory address.
+    pub fn instruction_bits(&self) -> usize {
+        6 + self.operand_bits()
+    }
+
+    /// Width of the operand field of an encoded instruction.
+    pub fn operand_bits(&self) -> usize {
+        usize::max(3 * self.register_bits(), self.rom_address_bits())
+    }
+}
+
+fn bits(size: usize) -> usize {
+    (usize::BITS - (size - 1).leading_zeros()).max(1) as usize
+}
+
+impl FromStr for MachineConfig {
+    type Err = ();
+
+    /// Parses `<rom size>,<ram size>,<register count>`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split(',').map(str::parse).collect::<Vec<_>>()[..] {
+            [Ok(rom), Ok(ram), Ok(registers)] => Self::new(rom, ram, registers).ok_or(()),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for MachineConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "{},{},{}",
+            self.rom_size, self.ram_size, self.register_count
+        )
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::instructions::Instruction;
+
+/// Execution count of a single program memory address.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileEntry {
+    /// Program memory address.
+    pub address: usize,
+    /// Instruction at the address.
+    pub instruction: Instruction,
+    /// Times the instruction was executed.
+    pub count: usize,
+}
+
+/// Executed addresses following a label, or preceding the first one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileGroup {
+    /// Label starting the group, `None` before the first label.
+    pub label: Option<String>,
+    /// Executions of all entries.
+    pub count: usize,
+    /// Executed addresses of the group.
+    pub entries: Vec<ProfileEntry>,
+}
+
+/// Hot-spot report of executed instructions, sorted by execution count.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    /// Executions of all instructions.
+    pub total: usize,
+    /// Groups of executed addresses.
+    pub groups: Vec<ProfileGroup>,
+}
+
+impl Profile {
+    fn percentage(&self, count: usize) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        writeln!(f, "Profile of {} instruction(s)", self.total)?;
+        for group in &self.groups {
+            let label = match &group.label {
+                Some(label) => format!("{label}:"),
+                None => "(no label)".into(),
+            };
+            writeln!(
+                f,
+                "{label:<28} {:>10} {:6.2}%",
+                group.count,
+                self.percentage(group.count)
+            )?;
+            for entry in &group.entries {
+                writeln!(
+                    f,
+                    "| {:#3} | {:<20} {:>10} {:6.2}%",
+                    entry.address,
+                    entry.instruction.to_string(),
+                    entry.count,
+                    self.percentage(entry.count)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt::{Display, Formatter, Result};
+
+use super::{EmulationError, StopReason};
+
+/// How a run of the processor ended.
+#[derive(Clone, Copy, Debug)]
+pub enum RunOutcome {
+    /// Reached a `halt` instruction or a jump to itself ending the program.
+    Halted,
+    /// Executed the last instruction of the program without halting.
+    FellOffEnd,
+    /// Stopped early by a breakpoint, watchpoint, step limit, detected loop
+    /// or interrupt.
+    Stopped(StopReason),
+    /// Failed to execute an instruction.
+    Error(EmulationError),
+}
+
+impl RunOutcome {
+    /// Process exit code reporting the outcome of a batch run.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Halted => 0,
+            Self::FellOffEnd => 1,
+            Self::Stopped(StopReason::StepLimit(_)) => 3,
+            Self::Stopped(StopReason::Loop { .. }) => 4,
+            Self::Stopped(StopReason::Interrupted) => 130,
+            Self::Stopped(_) => 2,
+            Self::Error(_) => 5,
+        }
+    }
+}
+
+impl Display for RunOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Halted => write!(f, "Halted"),
+            Self::FellOffEnd => write!(f, "Fell off the end of the program"),
+            Self::Stopped(reason) => write!(f, "{reason}"),
+            Self::Error(e) => write!(f, "Emulation error: {e:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use crate::instructions::Instruction;
+    use crate::processor::Processor;
+
+    fn processor(program: &[Instruction]) -> Processor {
+        let mut p = Processor::new();
+        p.load_rom(program);
+        p
+    }
+
+    #[test]
+    fn finished_programs_exit_with_0_or_1() {
+        let mut p = processor(&crate::asm![inc 0, 0; halt]);
+        assert_eq!(p.run(false, None).exit_code(), 0);
+        let mut p = processor(&crate::asm![inc 0, 0; jmp 1]);
+        assert_eq!(p.run(false, None).exit_code(), 0);
+        let mut p = processor(&crate::asm![inc 0, 0; inc 0, 0]);
+        assert_eq!(p.run(false, None).exit_code(), 1);
+    }
+
+    #[test]
+    fn stopped_runs_exit_by_reason() {
+        let mut p = processor(&crate::asm![inc 0, 0; inc 0, 0; halt]);
+        p.toggle_breakpoint(1);
+        assert_eq!(p.run(true, None).exit_code(), 2);
+
+        let mut p = processor(&crate::asm![inc 0, 0; jmp 0; halt]);
+        p.set_step_limit(Some(10));
+        assert_eq!(p.run(false, None).exit_code(), 3);
+
+        let mut p = processor(&crate::asm![jmp 0; halt]);
+        assert_eq!(p.run(false, None).exit_code(), 4);
+
+        let mut p = processor(&crate::asm![jmp 0; halt]);
+        let interrupt = Arc::new(AtomicBool::new(false));
+        p.set_interrupt(interrupt.clone());
+        p.set_loop_detection(false);
+        let ctrl_c = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            interrupt.store(true, Ordering::Relaxed);
+        });
+        assert_eq!(p.run(false, None).exit_code(), 130);
+        ctrl_c.join().unwrap();
+    }
+
+    #[test]
+    fn failed_instructions_exit_with_5() {
+        let mut p = processor(&crate::asm![sreg 1, 300; ld 0, 1; halt]);
+        assert_eq!(p.run(false, None).exit_code(), 5);
+    }
+}
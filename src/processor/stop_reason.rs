@@ -0,0 +1,93 @@
+use std::fmt::{Display, Formatter, Result};
+
+use super::watchpoint::{RegisterWatch, Watchpoint};
+use super::Flag;
+
+/// Why a run stopped before the program halted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StopReason {
+    /// Breakpoint at the given program memory address was reached.
+    Breakpoint(usize),
+    /// Condition of the breakpoint at the given address failed to evaluate.
+    BreakpointCondition(usize),
+    /// Watched memory cell was accessed.
+    Watchpoint {
+        /// Watchpoint which triggered.
+        watchpoint: Watchpoint,
+        /// Value before the access.
+        old: u16,
+        /// Value after the access.
+        new: u16,
+    },
+    /// Watched register changed.
+    RegisterWatch {
+        /// Watch which triggered.
+        watch: RegisterWatch,
+        /// Value before the change.
+        old: u16,
+        /// Value after the change.
+        new: u16,
+    },
+    /// Watched flag changed.
+    FlagWatch {
+        /// Flag which changed.
+        flag: Flag,
+        /// New value of the flag.
+        value: bool,
+    },
+    /// Program entered an infinite loop.
+    Loop {
+        /// First address of the loop.
+        start: usize,
+        /// Last address of the loop.
+        end: usize,
+    },
+    /// Run executed its maximum number of instructions.
+    StepLimit(usize),
+    /// Run was interrupted by the user.
+    Interrupted,
+}
+
+impl Display for StopReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Breakpoint(addr) => write!(f, "Breakpoint at {addr}"),
+            Self::BreakpointCondition(addr) => {
+                write!(f, "Breakpoint condition at {addr} cannot be evaluated")
+            }
+            Self::Watchpoint {
+                watchpoint,
+                old,
+                new,
+            } => {
+                if old == new {
+                    write!(f, "Watchpoint {watchpoint}: {new}")
+                } else {
+                    write!(f, "Watchpoint {watchpoint}: {old} -> {new}")
+                }
+            }
+            Self::RegisterWatch { watch, old, new } => {
+                if old == new {
+                    write!(f, "Register watch {watch}: {}", *new as i16)
+                } else {
+                    write!(
+                        f,
+                        "Register watch {watch}: {} -> {}",
+                        *old as i16, *new as i16
+                    )
+                }
+            }
+            Self::FlagWatch { flag, value } => {
+                write!(f, "Flag watch {flag}: {} -> {value}", !value)
+            }
+            Self::Loop { start, end } if start == end => {
+                write!(f, "Infinite loop: jump to itself at {start}")
+            }
+            Self::Loop { start, end } => {
+                write!(f, "Infinite loop: state repeats within {start}..{end}")
+            }
+            Self::StepLimit(limit) => write!(f, "Step limit of {limit} instruction(s) reached"),
+            Self::Interrupted => write!(f, "Interrupted"),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt::{Display, Formatter, Result};
+use std::str::FromStr;
+
+/// Access to a memory cell which triggers a [`Watchpoint`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WatchKind {
+    /// Any load from the cell.
+    Read,
+    /// Any store to the cell.
+    Write,
+    /// Store changing the value of the cell.
+    Change,
+}
+
+/// Stops execution on an access to a data memory cell.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Watchpoint {
+    /// Data memory address.
+    pub address: usize,
+    /// Accesses which trigger it.
+    pub kind: WatchKind,
+}
+
+/// Condition on the value written to a register. Thresholds are compared
+/// as signed values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RegisterWatchKind {
+    /// Any write.
+    Write,
+    /// Write changing the value.
+    Change,
+    /// Write moving the value across the threshold in either direction.
+    Threshold(i16),
+}
+
+/// Stops execution on a write to a register.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RegisterWatch {
+    /// Register index.
+    pub register: u8,
+    /// Writes which trigger it.
+    pub kind: RegisterWatchKind,
+}
+
+impl RegisterWatchKind {
+    /// Whether a write replacing `old` with `new` meets the condition.
+    pub fn triggered(&self, old: u16, new: u16) -> bool {
+        match *self {
+            Self::Write => true,
+            Self::Change => old != new,
+            Self::Threshold(threshold) => (old as i16 >= threshold) != (new as i16 >= threshold),
+        }
+    }
+}
+
+impl FromStr for WatchKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "r" | "read" => Ok(Self::Read),
+            "w" | "write" => Ok(Self::Write),
+            "c" | "change" => Ok(Self::Change),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for WatchKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::Write => write!(f, "write"),
+            Self::Change => write!(f, "change"),
+        }
+    }
+}
+
+impl Display for Watchpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "mem[{}] on {}", self.address, self.kind)
+    }
+}
+
+impl FromStr for RegisterWatchKind {
+    type Err = ();
+
+    /// Parses `write`, `change` or a threshold value.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "w" | "write" => Ok(Self::Write),
+            "c" | "change" => Ok(Self::Change),
+            _ => match s.parse::<i16>() {
+                Ok(threshold) => Ok(Self::Threshold(threshold)),
+                Err(_) => s
+                    .parse::<u16>()
+                    .map(|t| Self::Threshold(t as i16))
+                    .map_err(|_| ()),
+            },
+        }
+    }
+}
+
+impl Display for RegisterWatchKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Write => write!(f, "write"),
+            Self::Change => write!(f, "change"),
+            Self::Threshold(threshold) => write!(f, "crossing {threshold}"),
+        }
+    }
+}
+
+impl Display for RegisterWatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "R{} on {}", self.register, self.kind)
+    }
+}
@@ -0,0 +1,156 @@
+//! Per-instruction execution traces in JSON Lines or CSV format.
+
+use std::fmt::Display;
+use std::io::{Result, Write};
+use std::str::FromStr;
+
+use crate::instructions::Instruction;
+use crate::processor::FlagRegisters;
+
+mod diff;
+
+pub use diff::{diff, Difference, Divergence};
+
+/// State changes caused by a single executed instruction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceRecord {
+    /// Number of executed instructions, counting this one.
+    pub step: usize,
+    /// Address of the instruction.
+    pub program_counter: usize,
+    /// Executed instruction.
+    pub instruction: Instruction,
+    /// Register index and the value written to it.
+    pub register_write: Option<(u8, u16)>,
+    /// Data memory address and the value loaded from it.
+    pub memory_read: Option<(usize, u16)>,
+    /// Data memory address and the value stored to it.
+    pub memory_write: Option<(usize, u16)>,
+    /// Flags after the instruction.
+    pub flags: FlagRegisters,
+}
+
+/// Output format of a [`Tracer`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceFormat {
+    /// One JSON object per line.
+    JsonLines,
+    /// Comma-separated values with a header line.
+    Csv,
+}
+
+/// Writes trace records to an output in the chosen format.
+pub struct Tracer {
+    output: Box<dyn Write>,
+    format: TraceFormat,
+}
+
+impl FromStr for TraceFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "json" | "jsonl" => Ok(Self::JsonLines),
+            "csv" => Ok(Self::Csv),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Tracer {
+    /// Creates a tracer, writing the header of formats which have one.
+    pub fn new(mut output: Box<dyn Write>, format: TraceFormat) -> Result<Self> {
+        if format == TraceFormat::Csv {
+            writeln!(
+                output,
+                "step,pc,instruction,register,register_value,read_address,read_value,\
+                 write_address,write_value,zero,sign,carry"
+            )?;
+        }
+        Ok(Self { output, format })
+    }
+
+    /// Writes a single record.
+    pub fn record(&mut self, record: &TraceRecord) -> Result<()> {
+        match self.format {
+            TraceFormat::JsonLines => writeln!(self.output, "{}", record.to_json()),
+            TraceFormat::Csv => writeln!(self.output, "{}", record.to_csv()),
+        }
+    }
+
+    /// Flushes records buffered by the output.
+    pub fn flush(&mut self) -> Result<()> {
+        self.output.flush()
+    }
+}
+
+impl TraceRecord {
+    /// Record as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        let register_write = match self.register_write {
+            Some((index, value)) => format!("{{\"register\":{index},\"value\":{value}}}"),
+            None => "null".into(),
+        };
+        let memory = |access: Option<(usize, u16)>| match access {
+            Some((address, value)) => format!("{{\"address\":{address},\"value\":{value}}}"),
+            None => "null".into(),
+        };
+        format!(
+            "{{\"step\":{},\"pc\":{},\"instruction\":\"{}\",\"register_write\":{},\
+             \"memory_read\":{},\"memory_write\":{},\
+             \"flags\":{{\"zero\":{},\"sign\":{},\"carry\":{}}}}}",
+            self.step,
+            self.program_counter,
+            self.instruction
+                .to_string()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\""),
+            register_write,
+            memory(self.memory_read),
+            memory(self.memory_write),
+            self.flags.zero,
+            self.flags.sign,
+            self.flags.carry,
+        )
+    }
+
+    /// Record as a CSV row matching the [`Tracer`] header.
+    pub fn to_csv(&self) -> String {
+        let pair = |pair: Option<(String, u16)>| match pair {
+            Some((key, value)) => format!("{key},{value}"),
+            None => ",".into(),
+        };
+        format!(
+            "{},{},\"{}\",{},{},{},{},{},{}",
+            self.step,
+            self.program_counter,
+            self.instruction.to_string().replace('"', "\"\""),
+            pair(self.register_write.map(|(i, v)| (format!("R{i}"), v))),
+            pair(self.memory_read.map(|(a, v)| (a.to_string(), v))),
+            pair(self.memory_write.map(|(a, v)| (a.to_string(), v))),
+            self.flags.zero as u8,
+            self.flags.sign as u8,
+            self.flags.carry as u8,
+        )
+    }
+}
+
+impl Display for TraceRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#3} | {}", self.program_counter, self.instruction)?;
+        let mut effects = Vec::with_capacity(3);
+        if let Some((index, value)) = self.register_write {
+            effects.push(format!("R{index} = {value}"));
+        }
+        if let Some((address, value)) = self.memory_read {
+            effects.push(format!("read mem[{address}] = {value}"));
+        }
+        if let Some((address, value)) = self.memory_write {
+            effects.push(format!("mem[{address}] = {value}"));
+        }
+        if !effects.is_empty() {
+            write!(f, "  // {}", effects.join(", "))?;
+        }
+        Ok(())
+    }
+}
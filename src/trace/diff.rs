@@ -0,0 +1,163 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+
+use super::TraceRecord;
+use crate::processor::{EmulationError, FlagRegisters, Processor};
+
+/// State difference between two processors executing the same step. Values
+/// are given for the left processor, then the right one.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Difference {
+    /// Next instruction address.
+    ProgramCounter(usize, usize),
+    /// Register value, with the register index.
+    Register(u8, u16, u16),
+    /// Flags.
+    Flags(FlagRegisters, FlagRegisters),
+    /// Data memory value, with the address.
+    Memory(usize, u16, u16),
+    /// Address and value loaded, if any.
+    MemoryRead(Option<(usize, u16)>, Option<(usize, u16)>),
+}
+
+/// First step at which two executions differ, with the records of the
+/// preceding steps on both sides.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// Step after which the states differ, counting from 1.
+    pub step: usize,
+    /// Every difference found after the step.
+    pub differences: Vec<Difference>,
+    /// Records of the last steps up to the diverging one, `None` on a side
+    /// which already finished.
+    pub context: Vec<(Option<TraceRecord>, Option<TraceRecord>)>,
+}
+
+/// Runs both processors in lockstep until their states diverge, both
+/// programs finish or `max_steps` instructions are executed. Memory is
+/// compared where either side reads or writes it, so programs with
+/// different data only diverge once that data is used.
+pub fn diff(
+    left: &mut Processor,
+    right: &mut Processor,
+    context: usize,
+    max_steps: usize,
+) -> Result<Option<Divergence>, EmulationError> {
+    let mut history = VecDeque::with_capacity(context + 1);
+    for step in 1..=max_steps {
+        if left.finished() && right.finished() {
+            break;
+        }
+        let records = (advance(left)?, advance(right)?);
+        let differences = compare(left, right, &records);
+        history.push_back(records);
+        if history.len() > context + 1 {
+            history.pop_front();
+        }
+        if !differences.is_empty() {
+            return Ok(Some(Divergence {
+                step,
+                differences,
+                context: history.into(),
+            }));
+        }
+    }
+    Ok(None)
+}
+
+fn advance(p: &mut Processor) -> Result<Option<TraceRecord>, EmulationError> {
+    if p.finished() {
+        return Ok(None);
+    }
+    p.tick_traced().map(|(_, record)| record)
+}
+
+fn compare(
+    left: &Processor,
+    right: &Processor,
+    records: &(Option<TraceRecord>, Option<TraceRecord>),
+) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    if left.program_counter() != right.program_counter() {
+        differences.push(Difference::ProgramCounter(
+            left.program_counter(),
+            right.program_counter(),
+        ));
+    }
+    for (index, (l, r)) in left.registers().iter().zip(right.registers()).enumerate() {
+        if l != r {
+            differences.push(Difference::Register(index as u8, *l, *r));
+        }
+    }
+    if left.flags() != right.flags() {
+        differences.push(Difference::Flags(left.flags(), right.flags()));
+    }
+    let (l, r) = records;
+    let reads = (
+        l.as_ref().and_then(|l| l.memory_read),
+        r.as_ref().and_then(|r| r.memory_read),
+    );
+    if reads.0 != reads.1 {
+        differences.push(Difference::MemoryRead(reads.0, reads.1));
+    }
+    let mut writes = [l, r]
+        .into_iter()
+        .flatten()
+        .filter_map(|record| record.memory_write.map(|(address, _)| address))
+        .collect::<Vec<_>>();
+    writes.dedup();
+    for address in writes {
+        let (l, r) = (left.peek_memory(address), right.peek_memory(address));
+        if l != r {
+            differences.push(Difference::Memory(
+                address,
+                l.unwrap_or_default(),
+                r.unwrap_or_default(),
+            ));
+        }
+    }
+    differences
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let read = |access: &Option<(usize, u16)>| match access {
+            Some((address, value)) => format!("mem[{address}] = {value}"),
+            None => "none".into(),
+        };
+        match self {
+            Self::ProgramCounter(l, r) => write!(f, "Program counter: {l} != {r}"),
+            Self::Register(index, l, r) => write!(f, "R{index}: {l} != {r}"),
+            Self::Flags(l, r) => write!(f, "Flags: {} != {}", flags(l), flags(r)),
+            Self::Memory(address, l, r) => write!(f, "mem[{address}]: {l} != {r}"),
+            Self::MemoryRead(l, r) => write!(f, "Read: {} != {}", read(l), read(r)),
+        }
+    }
+}
+
+fn flags(flags: &FlagRegisters) -> String {
+    format!(
+        "[ zero: {} sign: {} carry: {} ]",
+        flags.zero, flags.sign, flags.carry
+    )
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Executions diverge at step {}:", self.step)?;
+        for difference in &self.differences {
+            writeln!(f, "  {difference}")?;
+        }
+        let first = self.step + 1 - self.context.len();
+        for (step, (l, r)) in (first..).zip(&self.context) {
+            writeln!(f, "\nStep {step}")?;
+            for (side, record) in [("<", l), (">", r)] {
+                match record {
+                    Some(record) => writeln!(f, "{side} {record}")?,
+                    None => writeln!(f, "{side} finished")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+//! VHDL sources for the instruction ROM and data RAM of the reference CPU.
+
+use crate::instructions::{Instruction, ToVhdl};
+use crate::processor::MachineConfig;
+use std::io::{Result, Write};
+
+/// Generates `instr_rom.vhd` containing the given program.
+pub fn instr_rom(rom: &[Instruction], config: &MachineConfig) -> Result<Vec<u8>> {
+    let values = [
+        ("address_msb", config.rom_address_bits() - 1),
+        ("instruction_msb", config.instruction_bits() - 1),
+    ];
+    let nop = "0".repeat(config.instruction_bits());
+    let rom_prefix = template(include_str!("../data/rom_prefix.vhd"), &values);
+    let rom_suffix = include_str!("../data/rom_suffix.vhd").replace("{nop}", &nop);
+    let mut vhdl = rom_prefix.into_bytes();
+    rom.iter().enumerate().try_for_each(|(addr, instr)| {
+        writeln!(
+            vhdl,
+            "\t\t\"{}\"  when iA = {addr} else",
+            instr.to_vhdl(config)
+        )
+    })?;
+    vhdl.extend_from_slice(rom_suffix.as_bytes());
+    Ok(vhdl)
+}
+
+/// Generates `data_ram.vhd` initialized with the given data.
+pub fn data_ram(ram: &[u16], config: &MachineConfig) -> Result<Vec<u8>> {
+    let values = [
+        ("address_msb", config.ram_address_bits() - 1),
+        ("last_address", config.ram_size() - 1),
+    ];
+    let ram_prefix = template(include_str!("../data/ram_prefix.vhd"), &values);
+    let ram_suffix = include_str!("../data/ram_suffix.vhd");
+    let mut vhdl = ram_prefix.into_bytes();
+    ram.iter()
+        .enumerate()
+        .try_for_each(|(index, value)| writeln!(vhdl, "\tsMEM({index}) <= x\"{value:04x}\";"))?;
+    vhdl.extend_from_slice(ram_suffix.as_bytes());
+    Ok(vhdl)
+}
+
+fn template(text: &str, values: &[(&str, usize)]) -> String {
+    values.iter().fold(text.to_owned(), |text, (key, value)| {
+        text.replace(&format!("{{{key}}}"), &value.to_string())
+    })
+}